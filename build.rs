@@ -1,22 +1,94 @@
 use std::{
     fs::{self, File},
     io::Write,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
+use serde::Deserialize;
+
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, imageops::FilterType};
+
+use syntect::highlighting::ThemeSet;
+use syntect::html::{ClassStyle, css_for_theme_with_class_style};
+use syntect::parsing::SyntaxSet;
+
+// Only the discovery/validation halves are needed here
+#[allow(dead_code)]
+#[path = "src/utils/bundles.rs"]
+mod bundles;
+#[allow(dead_code)]
+#[path = "src/utils/shortcodes.rs"]
+mod shortcodes;
+// Fence info parsing and `escape_html` for the diagrams
+#[allow(dead_code)]
+#[path = "src/utils/code.rs"]
+mod code;
+#[path = "src/utils/diagram.rs"]
+mod diagram;
+// The post's parse options and heading ids, to check `[[id#heading]]`
+#[allow(dead_code)]
+#[path = "src/utils/extensions.rs"]
+mod extensions;
+#[allow(dead_code)]
+#[path = "src/utils/headings.rs"]
+mod headings;
+#[allow(dead_code)]
+#[path = "src/utils/wikilinks.rs"]
+mod wikilinks;
+
+/// `(flavor, selector of the <html> state it applies to)`. The selectors are
+/// mutually exclusive, so rules of one flavor never outweigh another's.
+const SYNTAX_FLAVORS: &[(&str, &str)] = &[
+    ("Latte", ":root:not(.dark)"),
+    ("Mocha", ":root.dark:not(.frappe):not(.macchiato)"),
+    ("Frappe", ":root.dark.frappe"),
+    ("Macchiato", ":root.dark.macchiato"),
+];
+
+/// Widths of the responsive image variants. Images only get the steps below
+/// their own width, plus their own width when it is under the largest step.
+const IMAGE_WIDTHS: &[u32] = &[480, 960, 1600];
+
 fn main() {
     let articles_dir = Path::new("articles/published");
     let out_file = Path::new("src/utils/generated.rs");
 
     println!("cargo:rerun-if-changed=articles/published");
+    println!("cargo:rerun-if-changed=static/themes");
 
-    let mut entries = fs::read_dir(articles_dir)
-        .expect("Failed to read articles directory")
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-        .collect::<Vec<_>>();
+    println!("cargo:rerun-if-changed=static/syntaxes");
+    println!("cargo:rerun-if-changed=static/images");
 
-    entries.sort_by_key(|e| e.path());
+    generate_syntax_css(Path::new("static/syntax.css"));
+
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    generate_syntax_dump(
+        Path::new("static/syntaxes"),
+        &Path::new(&out_dir).join("syntaxes.packdump"),
+    );
+
+    let articles = bundles::discover(articles_dir);
+
+    check_shortcodes(&articles);
+    resolve_wikilinks(&articles, &Path::new(&out_dir).join("wikilinks.rs"));
+
+    // Bundle assets are published as is, and their images get variants too
+    let mut images = Vec::new();
+    collect_images(Path::new("static/images"), "/static/images", &mut images);
+    for (id, dir) in articles
+        .iter()
+        .filter_map(|a| Some((&a.id, a.bundle.as_ref()?)))
+    {
+        bundles::copy_assets(dir, &Path::new(bundles::BUNDLES_DIR).join(id));
+        collect_images(dir, &format!("{}/{id}", bundles::BUNDLES_URL), &mut images);
+    }
+    generate_image_variants(
+        &images,
+        Path::new("static/variants"),
+        &Path::new(&out_dir).join("images.rs"),
+    );
+
+    generate_diagrams(&articles, &Path::new(&out_dir).join("diagrams.rs"));
 
     let mut file = File::create(out_file).expect("Failed to create generated.rs");
 
@@ -29,25 +101,377 @@ fn main() {
     ];
         */
     writeln!(file, "pub const ARTICLES: &[(&str, &str)] = &[").unwrap();
-    for i in entries {
+    for article in &articles {
         writeln!(
             file,
-            "   (\"{}\", include_str!(\"../../articles/published/{}\")),",
-            i.path()
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string()
-                .as_str(),
-            i.path()
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string()
-                .as_str()
+            "   (\"{}\", include_str!(\"../../{}\")),",
+            article.id,
+            article.markdown.display()
         )
         .unwrap();
     }
     writeln!(file, "];").unwrap();
+
+    // Articles whose relative links point into `static/bundles/<id>/`
+    writeln!(file, "\npub const BUNDLES: &[&str] = &[").unwrap();
+    for article in articles.iter().filter(|a| a.bundle.is_some()) {
+        writeln!(file, "   \"{}\",", article.id).unwrap();
+    }
+    writeln!(file, "];").unwrap();
+}
+
+// A typo in a shortcode would otherwise only show up on the rendered page,
+// so refuse to build with any. Lines are counted from the top of the file.
+fn check_shortcodes(articles: &[bundles::ArticleSource]) {
+    let errors = articles
+        .iter()
+        .filter_map(|article| {
+            let path = &article.markdown;
+            let source = fs::read_to_string(path).expect("Failed to read article");
+            shortcodes::expand(&source)
+                .err()
+                .map(|err| format!("{}:{}: {}", path.display(), err.line, err.message))
+        })
+        .collect::<Vec<_>>();
+
+    if !errors.is_empty() {
+        panic!("invalid shortcodes:\n{}", errors.join("\n"));
+    }
+}
+
+/// The front matter keys wiki links need, see `utils::FrontMatter`.
+#[derive(Deserialize, Default)]
+struct Matter {
+    title: String,
+    published_at: String,
+    markdown: Option<extensions::ExtensionOverrides>,
+}
+
+// Same for `[[wiki links]]` to articles that do not exist (anymore), or to a
+// heading the article does not have. Headings get their ids the way
+// `markdown_to_html` gives them, from the post with its links and shortcodes
+// swapped in. The table of link targets is for `markdown_to_html`.
+fn resolve_wikilinks(articles: &[bundles::ArticleSource], table: &Path) {
+    let matter = gray_matter::Matter::<gray_matter::engine::YAML>::new();
+    let parsed = articles
+        .iter()
+        .map(|article| {
+            let path = &article.markdown;
+            let source = fs::read_to_string(path).expect("Failed to read article");
+            let result = matter
+                .parse::<Matter>(&source)
+                .unwrap_or_else(|err| panic!("Failed to parse {}: {err}", path.display()));
+            let matter = result.data.unwrap_or_default();
+            (article, source, matter, result.content)
+        })
+        .collect::<Vec<_>>();
+
+    // `Route::Articles`
+    let targets = parsed
+        .iter()
+        .map(|(article, _, matter, _)| {
+            let mut date = matter.published_at.split('-');
+            let path = format!(
+                "/articles/{}/{}/{}",
+                date.next().unwrap_or("0000"),
+                date.next().unwrap_or("00"),
+                article.id
+            );
+            (article.id.as_str(), path, matter.title.as_str())
+        })
+        .collect::<Vec<_>>();
+    let resolve = |link: &wikilinks::WikiLink| {
+        let (_, path, title) = targets.iter().find(|(id, _, _)| *id == link.id)?;
+        Some((path.clone(), title.to_string()))
+    };
+
+    let mut errors = Vec::new();
+    let mut heading_ids = Vec::new();
+    for (article, _, matter, content) in &parsed {
+        let extensions = extensions::SITE_EXTENSIONS.with_overrides(matter.markdown.as_ref());
+        // Unknown ids are reported with their line below
+        let linked = wikilinks::rewrite(content, resolve).unwrap_or_else(|_| content.clone());
+        let expanded = shortcodes::expand(&linked).map_or(linked, |expanded| expanded.source);
+
+        let arena = comrak::Arena::new();
+        let root = comrak::parse_document(&arena, &expanded, &extensions.options());
+        let ids = headings::heading_ids(root)
+            .into_iter()
+            .map(|(_, _, id)| id)
+            .collect::<Vec<_>>();
+        heading_ids.push((article.id.as_str(), ids));
+    }
+
+    for (article, source, _, _) in &parsed {
+        for (line, link) in wikilinks::find(source) {
+            let Some((_, ids)) = heading_ids.iter().find(|(id, _)| *id == link.id) else {
+                errors.push(format!(
+                    "{}:{}: no article `{}`",
+                    article.markdown.display(),
+                    line,
+                    link.id
+                ));
+                continue;
+            };
+            if let Some(heading) = link.heading
+                && !ids.contains(&format!("{}{heading}", headings::HEADING_ID_PREFIX))
+            {
+                errors.push(format!(
+                    "{}:{}: no heading `{heading}` in `{}`",
+                    article.markdown.display(),
+                    line,
+                    link.id
+                ));
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        panic!("broken wiki links:\n{}", errors.join("\n"));
+    }
+
+    let mut out = String::from("// AUTO-GENERATED by build.rs — DO NOT EDIT\n\n");
+    out.push_str("const LINK_TARGETS: &[LinkTarget] = &[\n");
+    for (id, path, title) in &targets {
+        out.push_str(&format!(
+            "    LinkTarget {{ id: {id:?}, path: {path:?}, title: {title:?} }},\n"
+        ));
+    }
+    out.push_str("];\n");
+    fs::write(table, out).expect("Failed to write wiki link table");
+}
+
+// Highlighted code comes out of comrak as spaced syntect classes
+// (`<span class="keyword control rust">`), this writes the colors of each
+// Catppuccin flavor for them, scoped to code blocks inside `.markdown`.
+fn generate_syntax_css(out_file: &Path) {
+    let mut css = String::from("/* AUTO-GENERATED by build.rs — DO NOT EDIT */\n\n");
+
+    for (flavor, root) in SYNTAX_FLAVORS {
+        let path = format!("static/themes/Catppuccin {flavor}.tmTheme");
+        let theme = ThemeSet::get_theme(&path).expect("Failed to parse theme file");
+        let theme_css = css_for_theme_with_class_style(&theme, ClassStyle::Spaced)
+            .expect("Failed to generate theme css");
+
+        let scope = format!("{root} .markdown pre");
+
+        for line in theme_css.lines() {
+            match line.strip_suffix(" {") {
+                Some(selectors) => {
+                    let scoped = selectors
+                        .split(", ")
+                        // `.code` holds the theme's base colors, it maps to the <pre>
+                        .map(|sel| match sel {
+                            ".code" => scope.clone(),
+                            sel => format!("{scope} {sel}"),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    css.push_str(&format!("{scoped} {{\n"));
+                }
+                None => {
+                    css.push_str(line);
+                    css.push('\n');
+                }
+            }
+        }
+        css.push('\n');
+    }
+
+    fs::write(out_file, css).expect("Failed to write syntax.css");
 }
 
+// syntect's default syntaxes plus every `.sublime-syntax` in `syntaxes_dir`
+// (Nix, TOML, justfile, ...), compiled once here into a packed dump that
+// `markdown_to_html` embeds with `include_bytes!`.
+fn generate_syntax_dump(syntaxes_dir: &Path, out_file: &Path) {
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    builder
+        .add_from_folder(syntaxes_dir, true)
+        .expect("Failed to load custom syntaxes");
+
+    syntect::dumps::dump_to_file(&builder.build(), out_file)
+        .expect("Failed to write syntax dump");
+}
+
+// Every diagram fence of the published articles, laid out once here rather
+// than on each render, for `utils::diagrams`. Fences are found with the same
+// parser the site uses; its extensions don't change what a fence holds.
+// Broken diagrams are left out with a warning and render as code.
+fn generate_diagrams(articles: &[bundles::ArticleSource], table: &Path) {
+    let mut options = comrak::Options::default();
+    options.extension.front_matter_delimiter = Some("---".to_string());
+
+    let mut diagrams: Vec<(String, String, String)> = Vec::new();
+    for article in articles {
+        let path = &article.markdown;
+        let source = fs::read_to_string(path).expect("Failed to read article");
+        let arena = comrak::Arena::new();
+        let root = comrak::parse_document(&arena, &source, &options);
+
+        for node in root.descendants() {
+            let comrak::nodes::NodeValue::CodeBlock(block) = &node.data.borrow().value else {
+                continue;
+            };
+            let fence = code::FenceInfo::parse(&block.info);
+            let Some(kind) = fence
+                .lang
+                .as_deref()
+                .and_then(diagram::DiagramKind::from_lang)
+            else {
+                continue;
+            };
+            // The same fence twice (on one page or across articles) is one entry
+            if diagrams
+                .iter()
+                .any(|(info, literal, _)| *info == block.info && *literal == block.literal)
+            {
+                continue;
+            }
+
+            let index = diagrams.len() + 1;
+            match diagram::render_diagram(kind, &block.literal, fence.title.as_deref(), index) {
+                Ok(html) => diagrams.push((block.info.clone(), block.literal.clone(), html)),
+                Err(err) => println!(
+                    "cargo:warning={}:{}: diagram left as code, {err}",
+                    path.display(),
+                    node.data.borrow().sourcepos.start.line
+                ),
+            }
+        }
+    }
+
+    let mut out = String::from("// AUTO-GENERATED by build.rs — DO NOT EDIT\n\n");
+    out.push_str("pub const DIAGRAMS: &[Diagram] = &[\n");
+    for (info, source, html) in &diagrams {
+        out.push_str(&format!(
+            "    Diagram {{ info: {info:?}, source: {source:?}, html: {html:?} }},\n"
+        ));
+    }
+    out.push_str("];\n");
+    fs::write(table, out).expect("Failed to write diagram table");
+}
+
+// `(file, public url)` of every raster image under `dir`, recursively.
+fn collect_images(dir: &Path, url_prefix: &str, images: &mut Vec<(PathBuf, String)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut entries = entries.filter_map(Result::ok).collect::<Vec<_>>();
+    entries.sort_by_key(|e| e.path());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let url = format!("{url_prefix}/{name}");
+
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+
+        if path.is_dir() {
+            collect_images(&path, &url, images);
+        } else if matches!(ext.as_deref(), Some("png" | "jpg" | "jpeg")) {
+            images.push((path, url));
+        }
+    }
+}
+
+// Resized WebP and AVIF copies of each image, both from pure-Rust encoders,
+// written to `variants_dir` (served as `/static/variants`). The manifest
+// lists them with the original dimensions for `utils::images`, which turns
+// markdown images into `<picture>` elements. A variant that is no smaller
+// than the original file is left out, the `<img>` fallback is cheaper.
+// Encoding AVIF is slow, so variants newer than their source are kept.
+fn generate_image_variants(images: &[(PathBuf, String)], variants_dir: &Path, manifest: &Path) {
+    fs::create_dir_all(variants_dir).expect("Failed to create variants directory");
+
+    let mut out = String::from("// AUTO-GENERATED by build.rs — DO NOT EDIT\n\n");
+    out.push_str("pub const IMAGES: &[Image] = &[\n");
+
+    for (path, url) in images {
+        let (width, height) = image::image_dimensions(path)
+            .unwrap_or_else(|err| panic!("Failed to read {}: {err}", path.display()));
+
+        let mut widths = IMAGE_WIDTHS
+            .iter()
+            .copied()
+            .filter(|w| *w < width)
+            .collect::<Vec<_>>();
+        if IMAGE_WIDTHS.last().is_some_and(|max| width <= *max) {
+            widths.push(width);
+        }
+
+        // `/static/images/a/b.png` -> `images-a-b`
+        let stem = url
+            .trim_start_matches("/static/")
+            .rsplit_once('.')
+            .map_or(url.as_str(), |(stem, _)| stem)
+            .replace('/', "-");
+
+        let original_len = fs::metadata(path).map_or(0, |m| m.len());
+        let mut source: Option<DynamicImage> = None;
+        let mut variants = Vec::new();
+
+        for w in widths {
+            let webp = variants_dir.join(format!("{stem}-{w}.webp"));
+            let avif = variants_dir.join(format!("{stem}-{w}.avif"));
+
+            if !is_fresh(&webp, path) || !is_fresh(&avif, path) {
+                let source = source.get_or_insert_with(|| {
+                    image::open(path)
+                        .unwrap_or_else(|err| panic!("Failed to decode {}: {err}", path.display()))
+                });
+                let resized = source.resize(w, u32::MAX, FilterType::Lanczos3);
+                encode_variant(&resized, &webp, &avif);
+            }
+
+            let variant_url = |file: &Path, ext: &str| {
+                let len = fs::metadata(file).map_or(u64::MAX, |m| m.len());
+                (len < original_len).then(|| format!("/static/variants/{stem}-{w}.{ext}"))
+            };
+            variants.push(format!(
+                "Variant {{ width: {w}, webp: {:?}, avif: {:?} }}",
+                variant_url(&webp, "webp"),
+                variant_url(&avif, "avif")
+            ));
+        }
+
+        out.push_str(&format!(
+            "    Image {{ src: {url:?}, width: {width}, height: {height}, variants: &[{}] }},\n",
+            variants.join(", ")
+        ));
+    }
+
+    out.push_str("];\n");
+    fs::write(manifest, out).expect("Failed to write image manifest");
+}
+
+fn is_fresh(variant: &Path, source: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    matches!((modified(variant), modified(source)), (Some(v), Some(s)) if v >= s)
+}
+
+fn encode_variant(image: &DynamicImage, webp: &Path, avif: &Path) {
+    // Both encoders take 8-bit RGB(A); keep alpha only when there is some
+    let (pixels, color) = if image.color().has_alpha() {
+        (image.to_rgba8().into_raw(), ExtendedColorType::Rgba8)
+    } else {
+        (image.to_rgb8().into_raw(), ExtendedColorType::Rgb8)
+    };
+    let (width, height) = (image.width(), image.height());
+
+    // The pure-Rust WebP encoder is lossless only, photos often come out
+    // bigger than their JPEG and are then dropped from the manifest
+    let file = File::create(webp).expect("Failed to create WebP variant");
+    image::codecs::webp::WebPEncoder::new_lossless(file)
+        .write_image(&pixels, width, height, color)
+        .expect("Failed to encode WebP variant");
+
+    let file = File::create(avif).expect("Failed to create AVIF variant");
+    image::codecs::avif::AvifEncoder::new_with_speed_quality(file, 8, 70)
+        .write_image(&pixels, width, height, color)
+        .expect("Failed to encode AVIF variant");
+}
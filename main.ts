@@ -1,6 +1,6 @@
 import { serve } from "https://deno.land/std@0.224.0/http/server.ts";
 import { serveDir } from "https://deno.land/std@0.224.0/http/file_server.ts";
-import init, { render } from "./pkg/yew_deno.js";
+import init, { render, render_head } from "./pkg/yew_deno.js";
 
 // Initialize Wasm
 const wasmUrl = new URL("./pkg/yew_deno_bg.wasm", import.meta.url);
@@ -34,20 +34,26 @@ serve(async (req) => {
   }
 
   try {
-    const appHtml = await render(url.pathname);
+    // Set by static/theme.js, lets the theme switcher render server-side
+    const theme = req.headers.get("cookie")?.match(/(?:^|;\s*)theme=([a-z]+)/)?.[1] ?? "system";
+    const appHtml = await render(url.pathname, url.search, theme);
+    const headHtml = render_head(url.pathname, url.search);
 
     const html = `
  
 
 <!DOCTYPE html>
-<html lang="en">
+<html lang="en" data-theme="${theme}">
   <head>
     <meta charset="UTF-8">
     <meta name="viewport" content="width=device-width, initial-scale=1">
     <meta name="description" content="Portfolio and articles by Abhinandh S - Software Engineer and Rust enthusiast.">
     <title>Abhinandh S</title>
     
+    <script src="/static/theme.js"></script>
+
     <link rel="stylesheet" href="/static/output.css"/>
+    <link rel="stylesheet" href="/static/syntax.css"/>
   
     <link rel="modulepreload" href="/pkg/yew_deno.js">
     <link rel="preload" href="/pkg/yew_deno_bg.wasm" as="fetch" type="application/wasm" crossorigin="anonymous">
@@ -64,6 +70,7 @@ serve(async (req) => {
     <link rel="alternate" type="application/feed+json" title="JSON Feed" href="/feed.json"/>
     <link rel="alternate" type="application/rss+xml" title="RSS" href="/feed.xml" />
     <link rel="alternate" type="application/atom+xml" title="Atom" href="/feed.atom.xml" />
+    ${headHtml}
   </head>
   <body>
     <div id="app">${appHtml}</div>
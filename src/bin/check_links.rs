@@ -0,0 +1,266 @@
+// Broken-link checker: renders every article through the site's markdown
+// pipeline and checks each `href`, `src` and `srcset` in the output.
+//
+// - internal paths must match a `Route`, article routes an existing
+//   article under its own year and month, and archive routes a year or
+//   month with articles in it
+// - `#fragments` must be an id in the target article (headings, footnotes)
+// - `/static/...` files must exist on disk
+//
+// External links are never fetched. With `--external-cache <file>`, a JSON
+// object of `url -> HTTP status` filled in by some other job, they are
+// checked against it and missing entries are reported as unchecked.
+//
+//     cargo run --bin check_links [-- --external-cache links.json]
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    process::ExitCode,
+};
+
+use yew_deno::{
+    Route,
+    utils::{self, Article, TocItem},
+};
+use yew_router::Routable;
+
+// Same as the feed generator's, absolute links to it are internal
+const SITE_URL: &str = "https://abhinandh-s.github.io/";
+
+struct Rendered {
+    ids: HashSet<String>,
+    links: Vec<String>,
+}
+
+enum Check {
+    Ok,
+    Broken(String),
+    /// External, and not in the cache.
+    Unchecked,
+}
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let mut cache_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--external-cache" => cache_path = args.next(),
+            _ => {
+                eprintln!("usage: check_links [--external-cache <file.json>]");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let cache = cache_path.map(|path| {
+        let json =
+            fs::read_to_string(&path).unwrap_or_else(|err| panic!("Failed to read {path}: {err}"));
+        serde_json::from_str::<HashMap<String, u16>>(&json)
+            .unwrap_or_else(|err| panic!("Failed to parse {path}: {err}"))
+    });
+
+    let mut articles = utils::get_all_articles();
+    articles.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let rendered = articles
+        .iter()
+        .map(|article| (article.id.clone(), render(article)))
+        .collect::<HashMap<_, _>>();
+
+    let mut broken = Vec::new();
+    let mut unchecked = Vec::new();
+
+    for article in &articles {
+        for link in &rendered[&article.id].links {
+            match check(link, article, &articles, &rendered, cache.as_ref()) {
+                Check::Ok => {}
+                Check::Broken(reason) => broken.push(format!("{}: {link}: {reason}", article.id)),
+                Check::Unchecked => unchecked.push(format!("{}: {link}", article.id)),
+            }
+        }
+    }
+
+    if !unchecked.is_empty() {
+        println!("{} external link(s) not in the cache:", unchecked.len());
+        for line in &unchecked {
+            println!("  {line}");
+        }
+    }
+
+    if broken.is_empty() {
+        println!("Checked {} articles, no broken links", articles.len());
+        return ExitCode::SUCCESS;
+    }
+
+    println!("{} broken link(s):", broken.len());
+    for line in &broken {
+        println!("  {line}");
+    }
+    ExitCode::FAILURE
+}
+
+fn render(article: &Article) -> Rendered {
+    let (toc, html) = utils::markdown_to_html(
+        &article.content,
+        &article.extensions(),
+        article.asset_base().as_deref(),
+    );
+
+    fn toc_ids(items: &[TocItem], ids: &mut HashSet<String>) {
+        for item in items {
+            ids.insert(item.id.clone());
+            toc_ids(&item.children, ids);
+        }
+    }
+
+    // The TOC only goes as deep as `toc_depth`, footnotes are not in it at all
+    let mut ids = attribute_values(&html, "id")
+        .into_iter()
+        .collect::<HashSet<_>>();
+    toc_ids(&toc, &mut ids);
+
+    let srcsets = attribute_values(&html, "srcset")
+        .into_iter()
+        .flat_map(|srcset| {
+            srcset
+                .split(',')
+                .filter_map(|candidate| candidate.split_whitespace().next())
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        });
+
+    let links = attribute_values(&html, "href")
+        .into_iter()
+        .chain(attribute_values(&html, "src"))
+        .chain(srcsets)
+        .collect();
+
+    Rendered { ids, links }
+}
+
+// The sanitizer reserializes everything, so attributes are always
+// double-quoted and `"` inside values is escaped.
+fn attribute_values(html: &str, name: &str) -> Vec<String> {
+    let needle = format!(" {name}=\"");
+    html.match_indices(&needle)
+        .filter_map(|(start, _)| html[start + needle.len()..].split_once('"'))
+        .map(|(value, _)| unescape(value))
+        .collect()
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn check(
+    link: &str,
+    article: &Article,
+    articles: &[Article],
+    rendered: &HashMap<String, Rendered>,
+    cache: Option<&HashMap<String, u16>>,
+) -> Check {
+    if let Some(fragment) = link.strip_prefix('#') {
+        return check_fragment(&article.id, fragment, rendered);
+    }
+
+    if link.starts_with("mailto:") || link.starts_with("tel:") {
+        return Check::Ok;
+    }
+
+    let path = match link.strip_prefix(SITE_URL) {
+        Some(path) => format!("/{path}"),
+        None if link.starts_with('/') && !link.starts_with("//") => link.to_string(),
+        None if link.contains("://") || link.starts_with("//") => {
+            return check_external(link, cache);
+        }
+        // Bundles had theirs rewritten to `/static/bundles/<id>/...`
+        None => return Check::Broken("relative link outside an article bundle".to_string()),
+    };
+
+    let (path, fragment) = path
+        .split_once('#')
+        .map_or((path.as_str(), None), |(path, fragment)| {
+            (path, Some(fragment))
+        });
+    let path = path.split('?').next().unwrap_or_default();
+
+    if path.starts_with("/static/") {
+        return match Path::new(path.trim_start_matches('/')).is_file() {
+            true => Check::Ok,
+            false => Check::Broken("no such static file".to_string()),
+        };
+    }
+
+    match Route::recognize(path) {
+        None | Some(Route::NotFound) => Check::Broken("no such route".to_string()),
+        Some(Route::Articles { year, month, id }) => {
+            let Some(target) = articles.iter().find(|a| a.id == id) else {
+                return Check::Broken(format!("no article `{id}`"));
+            };
+            if !target
+                .matter
+                .published_at
+                .starts_with(&format!("{year}-{month}-"))
+            {
+                return Check::Broken(format!(
+                    "article is published under {}",
+                    target.route().to_path()
+                ));
+            }
+            match fragment {
+                Some(fragment) => check_fragment(&id, fragment, rendered),
+                None => Check::Ok,
+            }
+        }
+        Some(Route::Series { slug }) => match utils::get_series(&slug) {
+            Some(_) => Check::Ok,
+            None => Check::Broken(format!("no series `{slug}`")),
+        },
+        // `/articles/2024/hello-world` parses as a month, and renders NotFound
+        Some(Route::ArchiveYear { year }) => check_archive(&year, None),
+        Some(Route::ArchiveMonth { year, month }) => check_archive(&year, Some(&month)),
+        Some(Route::ArticlesPage { page }) => {
+            match utils::paginate(articles.to_vec(), page, utils::ARTICLES_PER_PAGE) {
+                Some(_) => Check::Ok,
+                None => Check::Broken(format!("no page {page}")),
+            }
+        }
+        Some(_) => Check::Ok,
+    }
+}
+
+fn check_archive(year: &str, month: Option<&str>) -> Check {
+    match utils::get_articles_by_date(year, month).is_empty() {
+        false => Check::Ok,
+        true => Check::Broken(format!(
+            "no articles in {year}{}",
+            month.map(|month| format!("-{month}")).unwrap_or_default()
+        )),
+    }
+}
+
+fn check_fragment(id: &str, fragment: &str, rendered: &HashMap<String, Rendered>) -> Check {
+    match rendered[id].ids.contains(fragment) {
+        true => Check::Ok,
+        false => Check::Broken(format!("no `#{fragment}` in `{id}`")),
+    }
+}
+
+fn check_external(link: &str, cache: Option<&HashMap<String, u16>>) -> Check {
+    let Some(cache) = cache else {
+        return Check::Ok;
+    };
+    let url = link.split('#').next().unwrap_or(link);
+
+    match cache.get(url).or_else(|| cache.get(link)) {
+        Some(status) if *status < 400 => Check::Ok,
+        Some(status) => Check::Broken(format!("HTTP {status} (cached)")),
+        None => Check::Unchecked,
+    }
+}
@@ -8,13 +8,14 @@ fn main() {
     let articles_dir = Path::new("articles/published");
     let out_file = Path::new("src/utils/generated.rs");
 
-    let mut entries = fs::read_dir(articles_dir)
-        .expect("Failed to read articles directory")
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-        .collect::<Vec<_>>();
+    let articles = bundles::discover(articles_dir);
 
-    entries.sort_by_key(|e| e.path());
+    // Like build.rs, so the asset links in the feeds resolve
+    for article in &articles {
+        if let Some(dir) = &article.bundle {
+            bundles::copy_assets(dir, &Path::new(bundles::BUNDLES_DIR).join(&article.id));
+        }
+    }
 
     let mut file = File::create(out_file).expect("Failed to create generated.rs");
 
@@ -27,27 +28,23 @@ fn main() {
     ];
         */
     writeln!(file, "pub const ARTICLES: &[(&str, &str)] = &[").unwrap();
-    for i in entries {
+    for article in &articles {
         writeln!(
             file,
-            "   (\"{}\", include_str!(\"../../articles/published/{}\")),",
-            i.path()
-                .file_stem()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string()
-                .as_str(),
-            i.path()
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string()
-                .as_str()
+            "   (\"{}\", include_str!(\"../../{}\")),",
+            article.id,
+            article.markdown.display()
         )
         .unwrap();
     }
     writeln!(file, "];").unwrap();
 
+    writeln!(file, "\npub const BUNDLES: &[&str] = &[").unwrap();
+    for article in articles.iter().filter(|a| a.bundle.is_some()) {
+        writeln!(file, "   \"{}\",", article.id).unwrap();
+    }
+    writeln!(file, "];").unwrap();
+
     let _e = get_all_articles();
 
     let out_file = Path::new("static/feed.json");
@@ -61,11 +58,36 @@ fn main() {
 
 use serde::{Deserialize, Serialize};
 
+// Only the feed style is used here
+#[allow(dead_code)]
+#[path = "../utils/alerts.rs"]
+mod alerts;
+#[path = "../utils/bundles.rs"]
+mod bundles;
+#[path = "../utils/extensions.rs"]
+mod extensions;
+// Only the feed variant is used here
+#[allow(dead_code)]
+#[path = "../utils/sanitize.rs"]
+mod sanitize;
+#[path = "../utils/shortcodes.rs"]
+mod shortcodes;
+#[path = "../utils/stats.rs"]
+mod stats;
+#[allow(dead_code)]
+#[path = "../utils/wikilinks.rs"]
+mod wikilinks;
+
+use extensions::{ExtensionOverrides, MarkdownExtensions, SITE_EXTENSIONS};
+use stats::ReadingStats;
+use yew_deno::utils::escape_html;
+
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Article {
     pub id: String,
     pub matter: FrontMatter,
     pub content: String,
+    pub stats: ReadingStats,
 }
 
 // The default `Pod` data type can be a bit unwieldy, so
@@ -76,6 +98,28 @@ pub struct FrontMatter {
     pub published_at: String,
     pub snippet: String,
     pub tags: Option<Vec<String>>,
+    pub series: Option<String>,
+    pub series_order: Option<u32>,
+    pub markdown: Option<ExtensionOverrides>,
+}
+
+// Feed readers don't see the on-page series navigator, so prepend a note
+// pointing at the series landing page.
+fn series_note(article: &Article) -> String {
+    match &article.matter.series {
+        Some(name) => {
+            let slug = yew_deno::utils::slugify(name);
+            let part = article
+                .matter
+                .series_order
+                .map(|n| format!("Part {n} of "))
+                .unwrap_or_else(|| "Part of ".to_string());
+            // The name is plain text, unlike the rendered content around it
+            let name = escape_html(name);
+            format!(r#"<p><em>{part}the series <a href="{SITE_URL}series/{slug}">{name}</a>.</em></p>"#)
+        }
+        None => String::new(),
+    }
 }
 
 pub enum FileName {
@@ -102,38 +146,48 @@ pub fn path_as_string(path: &Path, f: FileName) -> String {
 }
 
 pub fn get_all_articles() -> Vec<Article> {
+    let mut parsed = Vec::new();
     let mut articles = Vec::new();
     let mut dbg = String::new();
     let articles_dir = Path::new("articles/published");
-    let mut entries = fs::read_dir(articles_dir)
-        .expect("Failed to read articles directory")
-        .filter_map(Result::ok)
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some("md"))
-        .collect::<Vec<_>>();
-
-    entries.sort_by_key(|e| e.path());
 
-    for i in entries {
-        let ctx =
-            read_to_string(i.path().to_string_lossy().to_string().as_str()).unwrap_or_default();
+    for source in bundles::discover(articles_dir) {
+        let ctx = read_to_string(&source.markdown).unwrap_or_default();
         let matter = gray_matter::Matter::<gray_matter::engine::YAML>::new();
         match matter.parse::<FrontMatter>(&ctx) {
-            Ok(result) => {
-                articles.push(Article {
-                    id: i
-                        .path()
-                        .file_stem()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string(),
-                    matter: result.data.unwrap_or_default(),
-                    content: markdown_to_html(&result.content),
-                });
-            }
+            Ok(result) => parsed.push((source, result.data.unwrap_or_default(), result.content)),
             Err(err) => dbg.push_str(err.to_string().as_str()),
         }
     }
 
+    // build.rs resolved wiki links already, feed readers need them absolute
+    let resolve = |link: &wikilinks::WikiLink| {
+        let (path, title) = yew_deno::utils::resolve_wikilink(link.id, link.heading)?;
+        Some((format!("{}{path}", SITE_URL.trim_end_matches('/')), title))
+    };
+
+    for (source, matter, content) in &parsed {
+        let extensions = SITE_EXTENSIONS.with_overrides(matter.markdown.as_ref());
+        // Feed readers resolve against the feed, not the post; go absolute
+        let asset_base = source.bundle.as_ref().map(|_| {
+            format!(
+                "{}{}/{}",
+                SITE_URL.trim_end_matches('/'),
+                bundles::BUNDLES_URL,
+                source.id
+            )
+        });
+        // build.rs checks wiki links first, this is only a backstop
+        let linked = wikilinks::rewrite(content, resolve)
+            .unwrap_or_else(|err| panic!("invalid wiki link in {}, {}", source.id, err));
+        articles.push(Article {
+            id: source.id.clone(),
+            matter: matter.clone(),
+            content: markdown_to_html(&linked, &extensions, asset_base.as_deref()),
+            stats: stats::reading_stats(content, &extensions),
+        });
+    }
+
     articles
 }
 
@@ -179,15 +233,102 @@ pub fn get_article_by_id(id: &str) -> Option<Article> {
 InspiredGitHub from here
 Solarized (dark) and Solarized (light)
  */
-pub fn markdown_to_html(source: &str) -> String {
+pub fn markdown_to_html(
+    source: &str,
+    extensions: &MarkdownExtensions,
+    asset_base: Option<&str>,
+) -> String {
     let adapter = comrak::plugins::syntect::SyntectAdapterBuilder::new()
         .theme("base16-ocean.dark")
         .build();
-    let options = comrak::Options::default();
+    let mut options = comrak::Options::default();
+    options.extension.math_dollars = true;
+    options.extension.math_code = true;
+    options.extension.alerts = true;
+    extensions.apply(&mut options);
     let mut plugins = comrak::options::Plugins::default();
 
     plugins.render.codefence_syntax_highlighter = Some(&adapter);
-    comrak::markdown_to_html_with_plugins(source, &options, &plugins)
+
+    // build.rs checks shortcodes first, this is only a backstop
+    let expanded =
+        shortcodes::expand(source).unwrap_or_else(|err| panic!("invalid shortcode, {}", err));
+
+    let arena = comrak::Arena::new();
+    let root = comrak::parse_document(&arena, &expanded.source, &options);
+
+    if let Some(base) = asset_base {
+        for node in root.descendants() {
+            if let comrak::nodes::NodeValue::Link(link) | comrak::nodes::NodeValue::Image(link) =
+                &mut node.data.borrow_mut().value
+                && let Some(url) = bundles::resolve(&link.url, base)
+            {
+                link.url = url;
+            }
+        }
+    }
+
+    // No Run button in a feed, but `rust,playground` should still highlight as Rust
+    for node in root.descendants() {
+        if let comrak::nodes::NodeValue::CodeBlock(block) = &mut node.data.borrow_mut().value {
+            block.info = block.info.replacen(",playground", "", 1);
+        }
+    }
+
+    // No alert styles in a feed reader, a labeled blockquote reads fine
+    alerts::render_alerts(root, alerts::Style::Feed, |value| {
+        &*arena.alloc(value.into())
+    });
+
+    // Feed readers mostly drop MathML, the TeX source is the readable fallback
+    let fallbacks = root
+        .descendants()
+        .filter_map(|node| {
+            let fallback = match &node.data.borrow().value {
+                comrak::nodes::NodeValue::Paragraph => {
+                    node.first_child()
+                        .and_then(|child| match &child.data.borrow().value {
+                            comrak::nodes::NodeValue::Text(text)
+                                if child.next_sibling().is_none() =>
+                            {
+                                expanded.lookup(text).map(str::to_string)
+                            }
+                            _ => None,
+                        })?
+                }
+                comrak::nodes::NodeValue::Math(m) if m.display_math => {
+                    format!(
+                        "<pre><code class=\"math\">{}</code></pre>",
+                        escape_html(&m.literal)
+                    )
+                }
+                comrak::nodes::NodeValue::Math(m) => {
+                    format!("<code class=\"math\">{}</code>", escape_html(&m.literal))
+                }
+                _ => return None,
+            };
+            Some((node, fallback))
+        })
+        .collect::<Vec<_>>();
+    for (node, fallback) in fallbacks {
+        // Shortcode placeholders, math nodes have no children
+        if let Some(child) = node.first_child() {
+            child.detach();
+        }
+        node.data.borrow_mut().value = comrak::nodes::NodeValue::Raw(fallback);
+    }
+
+    let mut html = String::new();
+    if let Err(err) = comrak::format_html_with_plugins(root, &options, &mut html, &plugins) {
+        html.push_str(err.to_string().as_str());
+    }
+
+    // Same allowlist as the site, plus the highlighter's inline colors
+    if extensions.raw_html {
+        html
+    } else {
+        sanitize::sanitize_feed(&html)
+    }
 }
 
 pub const SITE_URL: &str = "https://abhinandh-s.github.io/";
@@ -220,6 +361,9 @@ struct JsonFeedItem {
     date_published: String, // ISO 8601 format
     summary: Option<String>,
     banner_image: Option<String>,
+    // JSON Feed allows custom fields prefixed with an underscore
+    #[serde(rename = "_reading")]
+    reading: ReadingStats,
 }
 
 fn generate_json_feed() -> String {
@@ -229,11 +373,12 @@ fn generate_json_feed() -> String {
         .map(|article| JsonFeedItem {
             id: article.id.clone(),
             url: format!("{}articles/{}", HOME_PAGE_URL, article.id),
+            content_html: series_note(&article) + &article.content,
             title: article.matter.title,
-            content_html: article.content,
             date_published: format_rfc3339(&article.matter.published_at),
             summary: Some(article.matter.snippet),
             banner_image: None,
+            reading: article.stats,
         })
         .collect();
     let feed = JsonFeed {
@@ -279,7 +424,7 @@ fn generate_rss_feed() -> String {
         <guid>{site}articles/{id}</guid>
         <pubDate>{date}</pubDate>
         <description><![CDATA[{summary}]]></description>
-        <content:encoded><![CDATA[{content}]]></content:encoded>
+        <content:encoded><![CDATA[{note}{content}]]></content:encoded>
       </item>
 "#,
             title = article.matter.title,
@@ -287,6 +432,7 @@ fn generate_rss_feed() -> String {
             site = SITE_URL,
             date = format_rfc3339(&article.matter.published_at),
             summary = article.matter.snippet,
+            note = series_note(&article),
             content = article.content
         ));
     }
@@ -331,7 +477,7 @@ fn generate_atom_feed() -> String {
     <id>{site}articles/{id}</id>
     <updated>{updated}</updated>
     <summary>{summary}</summary>
-    <content type="html"><![CDATA[{content}]]></content>
+    <content type="html"><![CDATA[{note}{content}]]></content>
   </entry>
 "#,
             title = article.matter.title,
@@ -339,6 +485,7 @@ fn generate_atom_feed() -> String {
             site = SITE_URL,
             updated = format_rfc3339(&article.matter.published_at),
             summary = article.matter.snippet,
+            note = series_note(&article),
             content = article.content
         ));
     }
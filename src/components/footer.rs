@@ -12,6 +12,7 @@ pub fn footer() -> Html {
             class="p-2"
             href={format!("https://github.com/{}", crate::GITHUB_USERNAME)}
             target="_blank"
+            rel="noopener noreferrer"
           >
             <svg
               xmlns="http://www.w3.org/2000/svg"
@@ -65,7 +66,7 @@ pub fn footer() -> Html {
               <circle cx="5" cy="19" r="1"></circle>
             </svg>
           </a>
-          <a class="p-2" href="https://x.com/abhinandh_s_" target="_blank">
+          <a class="p-2" href="https://x.com/abhinandh_s_" target="_blank" rel="noopener noreferrer">
             <svg
               xmlns="http://www.w3.org/2000/svg"
               width="24"
@@ -90,6 +91,7 @@ pub fn footer() -> Html {
             href="https://yew.rs"
             aria-label="Link to framework used"
             target="_blank"
+            rel="noopener noreferrer"
           >
           { "Yew"}
           </a>{" "}
@@ -100,6 +102,7 @@ pub fn footer() -> Html {
             href="https://opensource.org/license/mit"
             aria-label="Link to Code License"
             target="_blank"
+            rel="noopener noreferrer"
           >
           { "MIT License" }
           </a>{" "}
@@ -109,6 +112,7 @@ pub fn footer() -> Html {
             href="https://github.com/abhinandh-s/abhinandh-s.github.io"
             aria-label="GitHub Profile"
             target="_blank"
+            rel="noopener noreferrer"
           >
           { "here" }
           </a>{". Articles are licensed under"}{" "}
@@ -117,6 +121,7 @@ pub fn footer() -> Html {
             href="https://creativecommons.org/licenses/by-sa/4.0/deed.en"
             aria-label="Link to Articles License"
             target="_blank"
+            rel="noopener noreferrer"
           >
           {"Creative Commons"}
           </a>{" "}
@@ -2,6 +2,7 @@ use yew::prelude::*;
 use yew_router::prelude::Link;
 
 use crate::Route;
+use crate::components::theme_switcher::ThemeSwitcher;
 
 const LINK_CLASS: &str = "hover:text-just-red aria-[current]:text-just-red";
 const MOBILE_LINK_CLASS: &str = "block py-2 px-4 hover:text-just-red aria-[current]:text-just-red";
@@ -33,6 +34,7 @@ pub fn header() -> Html {
               <div class="flex max-tablet:hidden space-x-16 mt-12 pb-7 px-16">
                 <Link<Route> to={Route::Home} classes={LINK_CLASS}>{ "Home" }</Link<Route>>
                 <Link<Route> to={Route::ArticlesRoute} classes={LINK_CLASS}>{ "Articles" }</Link<Route>>
+                <ThemeSwitcher />
               </div>
 
               /* Mobile Hamburger Menu */
@@ -63,6 +65,7 @@ pub fn header() -> Html {
             <div class={mobile_menu_class}>
               <Link<Route> to={Route::Home} classes={MOBILE_LINK_CLASS}>{ "Home" }</Link<Route>>
               <Link<Route> to={Route::ArticlesRoute} classes={MOBILE_LINK_CLASS}>{ "Articles" }</Link<Route>>
+              <div class="py-2 px-4"><ThemeSwitcher /></div>
             </div>
           </div>
         </nav>
@@ -1,2 +1,4 @@
 pub mod footer;
 pub mod header;
+pub mod reading_progress;
+pub mod theme_switcher;
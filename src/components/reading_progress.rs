@@ -0,0 +1,62 @@
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+/// Thin bar pinned to the top of the page, filled as the reader scrolls.
+/// Rendered empty on the server, it only moves after hydration.
+#[function_component(ReadingProgress)]
+pub fn reading_progress() -> Html {
+    let progress = use_state(|| 0.0_f64);
+
+    {
+        let progress = progress.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window();
+
+            let listener = window.as_ref().map(|window| {
+                let w = window.clone();
+                let listener =
+                    Closure::<dyn FnMut()>::new(move || progress.set(scroll_fraction(&w)));
+                let _ = window
+                    .add_event_listener_with_callback("scroll", listener.as_ref().unchecked_ref());
+                listener
+            });
+
+            move || {
+                if let (Some(window), Some(listener)) = (window, listener) {
+                    let _ = window.remove_event_listener_with_callback(
+                        "scroll",
+                        listener.as_ref().unchecked_ref(),
+                    );
+                }
+            }
+        });
+    }
+
+    html! {
+        <div class="fixed top-0 left-0 w-full h-1 z-50 pointer-events-none" aria-hidden="true">
+            <div
+                class="h-full bg-just-red transition-[width] duration-100"
+                style={format!("width: {:.2}%", *progress * 100.0)}
+            ></div>
+        </div>
+    }
+}
+
+fn scroll_fraction(window: &web_sys::Window) -> f64 {
+    let scrolled = window.scroll_y().unwrap_or_default();
+    let viewport = window
+        .inner_height()
+        .ok()
+        .and_then(|h| h.as_f64())
+        .unwrap_or_default();
+    let total = window
+        .document()
+        .and_then(|d| d.document_element())
+        .map(|e| e.scroll_height() as f64)
+        .unwrap_or_default();
+
+    match total - viewport {
+        max if max > 0.0 => (scrolled / max).clamp(0.0, 1.0),
+        _ => 0.0,
+    }
+}
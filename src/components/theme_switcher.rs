@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+use yew::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    // Defined by `static/theme.js`, persists the choice and sets the classes
+    // on <html>. Only ever called from event handlers, i.e. in the browser.
+    #[wasm_bindgen(js_namespace = window, js_name = __applyTheme)]
+    fn apply_theme(theme: &str);
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Theme {
+    #[default]
+    System,
+    Latte,
+    Frappe,
+    Macchiato,
+    Mocha,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 5] = [
+        Theme::System,
+        Theme::Latte,
+        Theme::Frappe,
+        Theme::Macchiato,
+        Theme::Mocha,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Theme::System => "system",
+            Theme::Latte => "latte",
+            Theme::Frappe => "frappe",
+            Theme::Macchiato => "macchiato",
+            Theme::Mocha => "mocha",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::System => "System",
+            Theme::Latte => "Light (Latte)",
+            Theme::Frappe => "Frappé",
+            Theme::Macchiato => "Macchiato",
+            Theme::Mocha => "Dark (Mocha)",
+        }
+    }
+}
+
+// Unknown values (a stale or tampered cookie) fall back to the system theme
+impl From<&str> for Theme {
+    fn from(value: &str) -> Self {
+        Theme::ALL
+            .into_iter()
+            .find(|t| t.as_str() == value)
+            .unwrap_or_default()
+    }
+}
+
+// `data-theme` on <html>, as set by `static/theme.js`. `None` on the server.
+fn document_theme() -> Option<Theme> {
+    let theme = web_sys::window()?
+        .document()?
+        .document_element()?
+        .get_attribute("data-theme")?;
+    Some(Theme::from(theme.as_str()))
+}
+
+#[function_component(ThemeSwitcher)]
+pub fn theme_switcher() -> Html {
+    // On the server the theme comes from the cookie, via the context set up
+    // in `App`. In the browser <html> already knows the current one.
+    let hint = use_context::<Theme>().unwrap_or_default();
+    let theme = use_state(|| document_theme().unwrap_or(hint));
+
+    let on_change = {
+        let theme = theme.clone();
+        Callback::from(move |e: Event| {
+            let select: web_sys::HtmlSelectElement = e.target_unchecked_into();
+            let next = Theme::from(select.value().as_str());
+            apply_theme(next.as_str());
+            theme.set(next);
+        })
+    };
+
+    html! {
+        <select
+            aria-label="Theme"
+            class="bg-transparent font-bold cursor-pointer hover:text-just-red outline-none"
+            onchange={on_change}
+        >
+            { for Theme::ALL.into_iter().map(|t| html! {
+                <option value={t.as_str()} selected={t == *theme}>{ t.label() }</option>
+            })}
+        </select>
+    }
+}
@@ -3,17 +3,26 @@ use yew::{LocalServerRenderer, prelude::*};
 use yew_router::history::{AnyHistory, History, MemoryHistory};
 use yew_router::prelude::*;
 
+use self::components::theme_switcher::Theme;
 use self::pages::articles::Article;
 
 mod components;
 mod pages;
-mod utils;
+pub mod utils;
 
 pub const NAME: &str = "Anonymous";
 pub const EMAIL: &str = "anonymous@proton.me";
 pub const GITHUB_USERNAME: &str = "Anonymous";
 pub const TWITTER_USERNAME: &str = "Anonymous";
 
+/// Where ` ```rust,playground ` snippets are sent to run: anything speaking
+/// the `play.rust-lang.org/execute` API. Set `PLAYGROUND_URL` at build time
+/// to point it at a local stand-in.
+pub const PLAYGROUND_URL: &str = match option_env!("PLAYGROUND_URL") {
+    Some(url) => url,
+    None => "https://play.rust-lang.org/execute",
+};
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Clone, Routable, PartialEq)]
 pub enum Route {
@@ -21,8 +30,16 @@ pub enum Route {
     Home,
     #[at("/articles")]
     ArticlesRoute,
+    #[at("/articles/page/:page")]
+    ArticlesPage { page: usize },
+    #[at("/articles/:year")]
+    ArchiveYear { year: String },
+    #[at("/articles/:year/:month")]
+    ArchiveMonth { year: String, month: String },
     #[at("/articles/:year/:month/:id")]
     Articles { year: String, month: String, id: String },
+    #[at("/series/:slug")]
+    Series { slug: String },
     #[not_found]
     #[at("/404")]
     NotFound,
@@ -36,6 +53,12 @@ fn switch(routes: Route) -> Html {
         
         },
         Route::ArticlesRoute => html! { <pages::articles::ArticleIndex /> },
+        Route::ArticlesPage { page } => html! { <pages::articles::ArticleIndex page={page} /> },
+        Route::ArchiveYear { year } => html! { <pages::articles::ArticleArchive year={year} /> },
+        Route::ArchiveMonth { year, month } => html! {
+            <pages::articles::ArticleArchive year={year} month={month} />
+        },
+        Route::Series { slug } => html! { <pages::series::SeriesPage slug={slug} /> },
         Route::NotFound => html! { <pages::_404::NotFound /> },
     }
 }
@@ -45,18 +68,31 @@ fn app(props: &AppProps) -> Html {
     if !props.path.is_empty() {
         // SERVER PATH: Use the provided path from Deno
         let history = AnyHistory::from(MemoryHistory::new());
-        history.push(&props.path);
+        // Deno hands us `url.search` verbatim, so drop the leading `?`
+        // before handing the pairs to the router.
+        match serde_urlencoded::from_str::<Vec<(String, String)>>(
+            props.query.trim_start_matches('?'),
+        ) {
+            Ok(query) if !query.is_empty() => {
+                let _ = history.push_with_query(&props.path, query);
+            }
+            _ => history.push(&props.path),
+        }
         html! {
-            <Router history={history}>
-                <Switch<Route> render={switch} />
-            </Router>
+            <ContextProvider<Theme> context={Theme::from(props.theme.as_str())}>
+                <Router history={history}>
+                    <Switch<Route> render={switch} />
+                </Router>
+            </ContextProvider<Theme>>
         }
     } else {
         // BROWSER PATH: Use the URL in the address bar
         html! {
-            <BrowserRouter>
-                <Switch<Route> render={switch} />
-            </BrowserRouter>
+            <ContextProvider<Theme> context={Theme::from(props.theme.as_str())}>
+                <BrowserRouter>
+                    <Switch<Route> render={switch} />
+                </BrowserRouter>
+            </ContextProvider<Theme>>
         }
     }
 }
@@ -64,14 +100,48 @@ fn app(props: &AppProps) -> Html {
 #[derive(Clone, PartialEq, Properties)]
 pub struct AppProps {
     pub path: String,
+    /// Raw query string of the request, e.g. `?q=yew&tag=rust`.
+    #[prop_or_default]
+    pub query: String,
+    /// Theme from the `theme` cookie, so SSR matches the stored choice.
+    #[prop_or_default]
+    pub theme: String,
 }
 
 #[wasm_bindgen]
-pub async fn render(path: String) -> String {
-    let renderer = LocalServerRenderer::<App>::with_props(AppProps { path });
+pub async fn render(path: String, query: String, theme: String) -> String {
+    let renderer = LocalServerRenderer::<App>::with_props(AppProps { path, query, theme });
     renderer.render().await
 }
 
+/// Extra `<head>` tags for `path` (currently `rel=prev/next` links),
+/// injected by the server next to the output of `render`. `query` is the
+/// same raw query string, so the links match the page's own pager.
+#[wasm_bindgen]
+pub fn render_head(path: String, query: String) -> String {
+    use self::pages::articles::{ArticleQuery, pager_href, pager_query, pager_routes};
+
+    let Some(route) = Route::recognize(&path) else {
+        return String::new();
+    };
+    let query = serde_urlencoded::from_str::<ArticleQuery>(query.trim_start_matches('?'))
+        .unwrap_or_default();
+    let (prev, next) = pager_routes(&route, &query);
+    let query = pager_query(&route, query);
+
+    [("prev", prev), ("next", next)]
+        .into_iter()
+        .filter_map(|(rel, link)| {
+            link.map(|(target, _)| {
+                // Percent-encoding leaves only the `&` between pairs to escape
+                let href = pager_href(&target, query.as_ref()).replace('&', "&amp;");
+                format!(r#"<link rel="{rel}" href="{href}"/>"#)
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[wasm_bindgen(start)]
 pub fn run_app() {
     // Check if we are in a browser environment
@@ -86,6 +156,8 @@ pub fn run_app() {
                 root,
                 AppProps {
                     path: String::new(),
+                    query: String::new(),
+                    theme: String::new(),
                 },
             )
             .hydrate();
@@ -5,11 +5,16 @@ pub struct ArticleProps {
     pub post_id: String,
 }
 
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
 use yew::prelude::*;
-use yew_router::prelude::Link;
+use yew_router::prelude::{Link, Navigator, Routable};
 
 use crate::Route;
-use crate::utils::{TocItem, get_article_by_id, get_date, markdown_to_html};
+use crate::utils::{
+    ARTICLES_PER_PAGE, Page, TocItem, get_archive_months, get_archive_years, get_article_by_id,
+    get_articles_by_date, get_date, get_month, markdown_to_html, paginate, select_toc,
+};
 
 #[function_component(ArticleEntryWithDate)]
 pub fn article_entry_with_date(props: &ArticleProps) -> Html {
@@ -46,86 +51,215 @@ pub fn article_entry_with_date(props: &ArticleProps) -> Html {
     }
 }
 
-#[derive(serde::Deserialize, PartialEq)]
-struct QueryParams {
-    tag: Option<String>,
+/// Search state of the article index. It lives in the URL as `?q=` and
+/// `?tag=` so SSR, refreshes and shared links all show the same list.
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ArticleQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub q: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl ArticleQuery {
+    pub fn is_empty(&self) -> bool {
+        self.q.is_none() && self.tag.is_none()
+    }
+
+    pub fn filter(&self, articles: Vec<crate::utils::Article>) -> Vec<crate::utils::Article> {
+        let query = self.q.as_deref().unwrap_or_default().trim().to_lowercase();
+
+        // Typing `#tag` into the search box behaves like `?tag=`
+        let (tag, text) = match query.strip_prefix('#') {
+            Some(target) => (Some(target.to_string()), String::new()),
+            None => (self.tag.as_ref().map(|t| t.to_lowercase()), query),
+        };
+
+        articles
+            .into_iter()
+            .filter(|a| {
+                tag.as_ref().is_none_or(|target| {
+                    a.matter
+                        .tags
+                        .as_ref()
+                        .is_some_and(|t_list| t_list.iter().any(|t| t.to_lowercase() == *target))
+                })
+            })
+            .filter(|a| {
+                text.is_empty()
+                    || a.matter.title.to_lowercase().contains(&text)
+                    || a.matter.snippet.to_lowercase().contains(&text)
+            })
+            .collect()
+    }
+}
+
+fn navigate_with_query(navigator: &Navigator, query: &ArticleQuery) {
+    if query.is_empty() {
+        navigator.replace(&Route::ArticlesRoute);
+    } else {
+        let _ = navigator.replace_with_query(&Route::ArticlesRoute, query);
+    }
+}
+
+/// A pager link: the target route and its label.
+pub type PagerLink = Option<(Route, String)>;
+
+fn page_route(number: usize) -> Route {
+    match number {
+        1 => Route::ArticlesRoute,
+        n => Route::ArticlesPage { page: n },
+    }
+}
+
+fn index_pager(page: &Page) -> (PagerLink, PagerLink) {
+    let prev = page
+        .has_prev()
+        .then(|| (page_route(page.number - 1), "Newer posts".to_string()));
+    let next = page
+        .has_next()
+        .then(|| (page_route(page.number + 1), "Older posts".to_string()));
+    (prev, next)
+}
+
+// Archive neighbours run chronologically: `prev` is the older period
+fn archive_pager(year: &str, month: Option<&str>) -> (PagerLink, PagerLink) {
+    match month {
+        None => {
+            let years = get_archive_years();
+            let Some(idx) = years.iter().position(|(y, _)| y == year) else {
+                return (None, None);
+            };
+            let link = |(y, _): &(String, usize)| (Route::ArchiveYear { year: y.clone() }, y.clone());
+            (years.get(idx + 1).map(link), idx.checked_sub(1).map(|i| link(&years[i])))
+        }
+        Some(month) => {
+            let months = get_archive_months();
+            let Some(idx) = months.iter().position(|(y, m)| y == year && m == month) else {
+                return (None, None);
+            };
+            let link = |(y, m): &(String, String)| {
+                (
+                    Route::ArchiveMonth {
+                        year: y.clone(),
+                        month: m.clone(),
+                    },
+                    get_month(y, m),
+                )
+            };
+            (months.get(idx + 1).map(link), idx.checked_sub(1).map(|i| link(&months[i])))
+        }
+    }
+}
+
+/// Prev/next targets of the listing pages, used both by `Pager` and by
+/// the server to emit `rel=prev/next` head links. The index pages through
+/// the articles matching `query`, like `ArticleIndex` does.
+pub fn pager_routes(route: &Route, query: &ArticleQuery) -> (PagerLink, PagerLink) {
+    let number = match route {
+        Route::ArticlesRoute => 1,
+        Route::ArticlesPage { page } => *page,
+        Route::ArchiveYear { year } => return archive_pager(year, None),
+        Route::ArchiveMonth { year, month } => return archive_pager(year, Some(month)),
+        _ => return (None, None),
+    };
+
+    paginate(
+        query.filter(crate::utils::get_all_articles_sorted()),
+        number,
+        ARTICLES_PER_PAGE,
+    )
+    .map(|page| index_pager(&page))
+    .unwrap_or_default()
+}
+
+/// The query the pager links of `route` carry: the index keeps its search
+/// filter from page to page, the archives have none.
+pub fn pager_query(route: &Route, query: ArticleQuery) -> Option<ArticleQuery> {
+    let is_index = matches!(route, Route::ArticlesRoute | Route::ArticlesPage { .. });
+    (is_index && !query.is_empty()).then_some(query)
+}
+
+/// Where a pager link goes, query included, as `Link` renders it.
+pub fn pager_href(route: &Route, query: Option<&ArticleQuery>) -> String {
+    let query = query
+        .and_then(|query| serde_urlencoded::to_string(query).ok())
+        .filter(|query| !query.is_empty());
+    match query {
+        Some(query) => format!("{}?{query}", route.to_path()),
+        None => route.to_path(),
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ArticleIndexProps {
+    #[prop_or(1)]
+    pub page: usize,
 }
 
 #[function_component(ArticleIndex)]
-pub fn article_index() -> Html {
-    let search_query = use_state(String::new);
+pub fn article_index(props: &ArticleIndexProps) -> Html {
     let location = yew_router::hooks::use_location().unwrap();
+    let navigator = yew_router::hooks::use_navigator().unwrap();
     let all_articles = use_memo((), |_| crate::utils::get_all_articles_sorted());
 
-    // EFFECT: Sync URL query params to the search state
-    {
-        let search_query = search_query.clone();
-        use_effect_with(location, move |loc| {
-            if let Ok(params) = loc.query::<QueryParams>()
-                && let Some(tag) = params.tag
-            {
-                search_query.set(format!("#{}", tag));
-            }
-            || ()
-        });
-    }
+    // The URL is the single source of truth for the search state
+    let query = location.query::<ArticleQuery>().unwrap_or_default();
 
     // For Tags
     let on_tag_click = {
-        let search_query = search_query.clone();
+        let navigator = navigator.clone();
+        let query = query.clone();
         Callback::from(move |tag: String| {
-            search_query.set(format!("#{}", tag)); // Prepend # for tag search
+            let _ = navigator.push_with_query(
+                &Route::ArticlesRoute,
+                &ArticleQuery {
+                    tag: Some(tag),
+                    ..query.clone()
+                },
+            );
         })
     };
 
-    // Filter logic
-    let filtered_articles = {
-        let query = (*search_query).to_lowercase();
-        let articles = (*all_articles).clone();
-
-        if query.is_empty() {
-            articles
-        } else if query.starts_with('#') {
-            // Tag search logic
-            let target = query.trim_start_matches('#');
-            articles
-                .into_iter()
-                .filter(|a| {
-                    a.matter
-                        .tags
-                        .as_ref()
-                        .map(|t_list| t_list.iter().any(|t| t.to_lowercase() == target))
-                        .unwrap_or(false)
-                })
-                .collect()
-        } else {
-            // Normal text search logic
-            articles
-                .into_iter()
-                .filter(|a| {
-                    a.matter.title.to_lowercase().contains(&query)
-                        || a.matter.snippet.to_lowercase().contains(&query)
-                })
-                .collect()
-        }
+    let on_clear_tag = {
+        let navigator = navigator.clone();
+        let query = query.clone();
+        Callback::from(move |_| {
+            navigate_with_query(
+                &navigator,
+                &ArticleQuery {
+                    tag: None,
+                    ..query.clone()
+                },
+            );
+        })
     };
 
     let on_input = {
-        let search_query = search_query.clone();
-        let navigator = yew_router::hooks::use_navigator().unwrap();
+        let navigator = navigator.clone();
+        let query = query.clone();
         Callback::from(move |e: InputEvent| {
             let input: web_sys::HtmlInputElement = e.target_unchecked_into();
             let val = input.value();
 
-            // If the user starts typing, remove the ?tag= from the URL
-            if !val.starts_with('#') {
-                navigator.replace(&Route::ArticlesRoute);
-            }
-
-            search_query.set(val);
+            navigate_with_query(
+                &navigator,
+                &ArticleQuery {
+                    q: (!val.is_empty()).then_some(val),
+                    ..query.clone()
+                },
+            );
         })
     };
 
+    let filtered_articles = query.filter((*all_articles).clone());
+    let found = filtered_articles.len();
+
+    let Some(page) = paginate(filtered_articles, props.page, ARTICLES_PER_PAGE) else {
+        return html! { <crate::pages::_404::NotFound /> };
+    };
+    let (prev, next) = index_pager(&page);
+
     html! {
       <>
         <crate::components::header::Header />
@@ -140,18 +274,31 @@ pub fn article_index() -> Html {
                 type="text"
                 placeholder="Search articles..."
                 class="w-full bg-surface0 text-text p-3 rounded-lg border border-surface1 focus:border-just-red outline-none transition-all"
+                value={query.q.clone().unwrap_or_default()}
                 oninput={on_input}
             />
             <span class="absolute right-3 top-3 text-subtext0">
-                { format!("{} found", filtered_articles.len()) }
+                { format!("{found} found") }
             </span>
           </div>
 
-          <TagCloud on_tag_click={on_tag_click} />
+          if let Some(tag) = query.tag.clone() {
+              <div class="mt-4 text-sm">
+                { "Filtered by tag " }
+                <span onclick={on_clear_tag}
+                      class="px-3 py-1 bg-surface0 text-blue rounded-full text-xs border border-blue cursor-pointer"
+                      title="Clear tag filter">
+                    { format!("{tag} ×") }
+                </span>
+              </div>
+          }
+
+          <TagCloud on_tag_click={on_tag_click} active={query.tag.clone()} />
+          <ArchiveYears />
 
           <ul class="mt-8">
             {
-              for filtered_articles.clone().into_iter().map(|article| {
+              for page.articles.into_iter().map(|article| {
                 let parts: Vec<&str> = article.matter.published_at.split('-').collect();
                 html! {
                     <ArticleEntryWithDate
@@ -164,10 +311,73 @@ pub fn article_index() -> Html {
             }
           </ul>
 
-          if filtered_articles.is_empty() {
+          if found == 0 {
               <p class="text-center text-subtext0 mt-10">{"No articles match your search."}</p>
           }
 
+          <Pager prev={prev} next={next} query={pager_query(&page_route(props.page), query.clone())} />
+
+          <div class="border-b border-surface1"></div>
+          <crate::components::footer::Footer />
+        </div>
+      </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ArchiveProps {
+    pub year: String,
+    #[prop_or_default]
+    pub month: Option<String>,
+}
+
+// https://abhinandhs.deno.dev/articles/:year(/:month)
+//                                        ^
+//                                        this page
+#[function_component(ArticleArchive)]
+pub fn article_archive(props: &ArchiveProps) -> Html {
+    let articles = get_articles_by_date(&props.year, props.month.as_deref());
+
+    if articles.is_empty() {
+        return html! { <crate::pages::_404::NotFound /> };
+    }
+
+    let title = match &props.month {
+        Some(month) => get_month(&props.year, month),
+        None => props.year.clone(),
+    };
+    let (prev, next) = archive_pager(&props.year, props.month.as_deref());
+
+    html! {
+      <>
+        <crate::components::header::Header />
+        <div class="p-4 mx-auto max-w-3xl flex flex-col justify-center">
+          <h1 class="font-bold text-5xl mt-12">
+            { title }<span class="text-just-red">{ "." }</span>
+          </h1>
+          <p class="mt-2 text-subtext0">
+            { format!("{} article{}", articles.len(), if articles.len() == 1 { "" } else { "s" }) }
+          </p>
+
+          <ArchiveYears active={props.year.clone()} />
+
+          <ul class="mt-8">
+            {
+              for articles.into_iter().map(|article| {
+                let parts: Vec<&str> = article.matter.published_at.split('-').collect();
+                html! {
+                    <ArticleEntryWithDate
+                        year={parts[0].to_string()}
+                        month={parts[1].to_string()}
+                        post_id={article.id}
+                    />
+                }
+              })
+            }
+          </ul>
+
+          <Pager prev={prev} next={next} />
+
           <div class="border-b border-surface1"></div>
           <crate::components::footer::Footer />
         </div>
@@ -175,6 +385,109 @@ pub fn article_index() -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+pub struct ArchiveYearsProps {
+    #[prop_or_default]
+    pub active: Option<String>,
+}
+
+#[function_component(ArchiveYears)]
+pub fn archive_years(props: &ArchiveYearsProps) -> Html {
+    html! {
+        <div class="pb-4 mt-4">
+            <h3 class="text-subtext1 font-bold mb-4 uppercase text-xs tracking-widest">{"Archive"}</h3>
+            <div class="flex flex-wrap gap-4 text-sm">
+                { for get_archive_years().into_iter().map(|(year, count)| {
+                    let is_active = props.active.as_ref() == Some(&year);
+                    html! {
+                        <Link<Route>
+                            to={Route::ArchiveYear { year: year.clone() }}
+                            classes={classes!("hover:text-just-red", is_active.then_some("text-just-red"))}
+                        >
+                            { format!("{year} ({count})") }
+                        </Link<Route>>
+                    }
+                })}
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PagerProps {
+    pub prev: PagerLink,
+    pub next: PagerLink,
+    /// Carried over so paging through search results keeps the filter.
+    #[prop_or_default]
+    pub query: Option<ArticleQuery>,
+}
+
+#[function_component(Pager)]
+pub fn pager(props: &PagerProps) -> Html {
+    // Keep `rel=prev/next` in sync after client-side navigation;
+    // the server renders the initial ones via `render_head`.
+    let href = |link: &PagerLink| {
+        link.as_ref()
+            .map(|(route, _)| pager_href(route, props.query.as_ref()))
+    };
+    let hrefs = (href(&props.prev), href(&props.next));
+    use_effect_with(hrefs, |(prev, next)| {
+        set_head_link("prev", prev.as_deref());
+        set_head_link("next", next.as_deref());
+        || {
+            set_head_link("prev", None);
+            set_head_link("next", None);
+        }
+    });
+
+    let link = |link: &PagerLink, rel: &'static str| match link {
+        Some((route, label)) => html! {
+            <Link<Route, ArticleQuery>
+                to={route.clone()}
+                query={props.query.clone()}
+                classes="hover:text-just-red"
+            >
+                { if rel == "prev" { format!("← {label}") } else { format!("{label} →") } }
+            </Link<Route, ArticleQuery>>
+        },
+        None => html! { <span></span> },
+    };
+
+    html! {
+        <nav class="flex justify-between my-8 font-bold" aria-label="Pagination">
+            { link(&props.prev, "prev") }
+            { link(&props.next, "next") }
+        </nav>
+    }
+}
+
+fn set_head_link(rel: &str, href: Option<&str>) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let existing = document
+        .query_selector(&format!("link[rel='{rel}']"))
+        .ok()
+        .flatten();
+
+    match (existing, href) {
+        (Some(link), Some(href)) => {
+            let _ = link.set_attribute("href", href);
+        }
+        (Some(link), None) => link.remove(),
+        (None, Some(href)) => {
+            if let Ok(link) = document.create_element("link")
+                && let Ok(Some(head)) = document.query_selector("head")
+            {
+                let _ = link.set_attribute("rel", rel);
+                let _ = link.set_attribute("href", href);
+                let _ = head.append_child(&link);
+            }
+        }
+        (None, None) => (),
+    }
+}
+
 // https://abhinandhs.deno.dev/articles/:post
 //                                            ^
 //                                            this page
@@ -219,17 +532,53 @@ pub fn article(props: &ArticleProps) -> Html {
             // Redirect to home with a query parameter
             let _ = navigator.push_with_query(
                 &Route::ArticlesRoute,
-                &std::collections::HashMap::from([("tag", tag)]),
+                &ArticleQuery {
+                    tag: Some(tag),
+                    ..Default::default()
+                },
             );
         })
     };
 
+    // The rendered markdown is raw HTML outside of Yew's control, so its
+    // copy buttons are handled by delegation from the wrapping div
+    let on_markdown_click = Callback::from(|e: MouseEvent| {
+        let Some(target) = e.target_dyn_into::<web_sys::Element>() else {
+            return;
+        };
+        if let Some(button) = target.closest(".heading-copy").ok().flatten() {
+            copy_section_link(&button);
+            return;
+        }
+        if let Some(button) = target.closest(".code-run").ok().flatten() {
+            run_on_playground(button);
+            return;
+        }
+
+        let Some(button) = target.closest(".code-copy").ok().flatten() else {
+            return;
+        };
+        let Some(code) = button
+            .closest(".code-block")
+            .ok()
+            .flatten()
+            .and_then(|block| block.query_selector("code").ok().flatten())
+        else {
+            return;
+        };
+
+        // Line numbers are CSS counters, so they are not part of the text
+        copy_to_clipboard(&code.text_content().unwrap_or_default());
+        flash_text(&button, "Copied!", "Copy");
+    });
+
     match get_article_by_id(&props.post_id) {
         Some(post) => {
-            let word_count = &post.content.split_whitespace().count();
-            let reading_time = (*word_count as f32 / 200.0).ceil();
+            let stats = post.stats();
+            let structured_data = json_ld(&post, &stats);
 
-            let (toc_items, html) = markdown_to_html(&post.content);
+            let (toc_items, html) = markdown_to_html(&post.content, &post.extensions(), post.asset_base().as_deref());
+            let toc_items = select_toc(toc_items, &post.matter);
             let ctx = Html::from_html_unchecked(html.into());
             let org = post.matter.published_at;
             let date = get_date(org.clone().as_str(), true);
@@ -243,11 +592,15 @@ pub fn article(props: &ArticleProps) -> Html {
 
             html! {
                             <>
+                              <crate::components::reading_progress::ReadingProgress />
                               <crate::components::header::Header />
+                              { structured_data }
 
                               <div class="flex flex-col lg:flex-row relative max-w-7xl mx-auto w-full">
               <aside class="max-tablet:hidden w-64 flex-shrink-0 sticky top-20 self-start h-fit p-4">
-                      <TableOfContents toc_items={toc_items} />
+                      if let Some(toc_items) = toc_items {
+                          <TableOfContents toc_items={toc_items} />
+                      }
             <TagCloud on_tag_click={&on_tag_click} />
                   </aside>
 
@@ -255,13 +608,20 @@ pub fn article(props: &ArticleProps) -> Html {
                       <p class="font-bold mt-12 text-mocha-overlay2">{ date }</p>
                       <h1 class="font-bold text-5xl mt-2 leading-tight">{ post.matter.title }</h1>
 
-                      <p>{ format!("Reading Time: ~ {reading_time} minutes") }</p>
+                      <p>{ format!("Reading Time: ~ {} minutes · {} words", stats.minutes, stats.words) }</p>
             <CTagCloud on_tag_click={on_tag_click} tags={tags} />
+                      if let Some(series) = post.matter.series.clone() {
+                          <crate::pages::series::SeriesNav series={series} current={post.id.clone()} />
+                      }
 
-                      <div class="markdown mt-12 overflow-x-auto">
+                      <div class="markdown mt-12 overflow-x-auto" onclick={on_markdown_click}>
                           // ^ added overflow-x-auto to prevent wide code blocks from breaking mobile
                           { ctx }
                       </div>
+
+                      <ArticleNeighbours current={post.id.clone()} />
+                      <Backlinks current={post.id.clone()} />
+                      <RelatedPosts current={post.id.clone()} />
                   </main>
 
 
@@ -274,6 +634,251 @@ pub fn article(props: &ArticleProps) -> Html {
     }
 }
 
+fn copy_to_clipboard(text: &str) {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    // `navigator.clipboard.writeText`, looked up dynamically as it is
+    // missing on insecure origins
+    let navigator = window.navigator();
+    let Ok(clipboard) = js_sys::Reflect::get(&navigator, &"clipboard".into()) else {
+        return;
+    };
+    if let Ok(write_text) = js_sys::Reflect::get(&clipboard, &"writeText".into())
+        .and_then(|f| f.dyn_into::<js_sys::Function>())
+    {
+        let _ = write_text.call1(&clipboard, &text.into());
+    }
+}
+
+// Shows `text` on `element` for a moment, then puts `restore` back
+fn flash_text(element: &web_sys::Element, text: &str, restore: &'static str) {
+    element.set_text_content(Some(text));
+
+    if let Some(window) = web_sys::window() {
+        let element = element.clone();
+        let reset = Closure::once_into_js(move || element.set_text_content(Some(restore)));
+        let _ = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(reset.unchecked_ref(), 2000);
+    }
+}
+
+// Sets `name` on `element` for a moment, so CSS can show it worked
+fn flash_attribute(element: &web_sys::Element, name: &'static str) {
+    let _ = element.set_attribute(name, "");
+
+    if let Some(window) = web_sys::window() {
+        let element = element.clone();
+        let reset = Closure::once_into_js(move || {
+            let _ = element.remove_attribute(name);
+        });
+        let _ = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(reset.unchecked_ref(), 2000);
+    }
+}
+
+// The page URL with the heading's id as fragment, so it works on any host
+fn copy_section_link(button: &web_sys::Element) {
+    let Some(id) = button.get_attribute("data-heading") else {
+        return;
+    };
+    let Some(url) = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.url().ok())
+    else {
+        return;
+    };
+
+    let page = url.split('#').next().unwrap_or_default();
+    copy_to_clipboard(&format!("{page}#{id}"));
+    flash_attribute(button, "data-copied");
+}
+
+// Sends the snippet to `PLAYGROUND_URL` and shows what it printed in a
+// `pre.code-output` under the code, reused by later runs
+fn run_on_playground(button: web_sys::Element) {
+    let Some(block) = button.closest(".code-block").ok().flatten() else {
+        return;
+    };
+    let Some(code) = block
+        .query_selector("code")
+        .ok()
+        .flatten()
+        .and_then(|code| code.text_content())
+    else {
+        return;
+    };
+    if button.has_attribute("disabled") {
+        return;
+    }
+
+    let _ = button.set_attribute("disabled", "");
+    button.set_text_content(Some("Running…"));
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let output = match execute_on_playground(&code).await {
+            Ok(output) if output.trim().is_empty() => "(no output)".to_string(),
+            Ok(output) => output,
+            Err(_) => "Could not reach the playground.".to_string(),
+        };
+
+        let existing = block.query_selector(".code-output").ok().flatten();
+        let pre = existing.or_else(|| {
+            let pre = web_sys::window()?.document()?.create_element("pre").ok()?;
+            pre.set_class_name("code-output");
+            let _ = pre.set_attribute("aria-live", "polite");
+            block.append_child(&pre).ok()?;
+            Some(pre)
+        });
+        if let Some(pre) = pre {
+            pre.set_text_content(Some(&output));
+        }
+
+        let _ = button.remove_attribute("disabled");
+        button.set_text_content(Some("Run"));
+    });
+}
+
+// Compiler messages come back in `stderr`, the program's own in `stdout`
+async fn execute_on_playground(code: &str) -> Result<String, JsValue> {
+    let body = serde_json::json!({
+        "channel": "stable",
+        "mode": "debug",
+        "edition": "2024",
+        "crateType": "bin",
+        "tests": false,
+        "backtrace": false,
+        "code": code,
+    });
+
+    let headers = js_sys::Object::new();
+    js_sys::Reflect::set(&headers, &"Content-Type".into(), &"application/json".into())?;
+    let init = web_sys::RequestInit::new();
+    init.set_method("POST");
+    init.set_headers(&headers);
+    init.set_body(&body.to_string().into());
+
+    let window = web_sys::window().ok_or(JsValue::NULL)?;
+    let response = wasm_bindgen_futures::JsFuture::from(
+        window.fetch_with_str_and_init(crate::PLAYGROUND_URL, &init),
+    )
+    .await?
+    .dyn_into::<web_sys::Response>()?;
+    if !response.ok() {
+        return Err(JsValue::from(response.status()));
+    }
+    let json = wasm_bindgen_futures::JsFuture::from(response.json()?).await?;
+
+    let field = |name: &str| {
+        js_sys::Reflect::get(&json, &name.into())
+            .ok()
+            .and_then(|value| value.as_string())
+            .unwrap_or_default()
+    };
+    Ok(format!("{}{}", field("stderr"), field("stdout")))
+}
+
+// schema.org `BlogPosting` for search engines, rendered as a raw `<script>`
+// so the JSON is not HTML-escaped.
+fn json_ld(post: &crate::utils::Article, stats: &crate::utils::ReadingStats) -> Html {
+    let data = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        "headline": post.matter.title,
+        "description": post.matter.snippet,
+        "datePublished": post.matter.published_at,
+        "keywords": post.matter.tags.clone().unwrap_or_default(),
+        "wordCount": stats.words,
+        "timeRequired": format!("PT{}M", stats.minutes),
+        "author": { "@type": "Person", "name": crate::NAME },
+    });
+    // `</script>` inside a string would otherwise end the element early
+    let data = data.to_string().replace("</", "<\\/");
+
+    Html::from_html_unchecked(format!(r#"<script type="application/ld+json">{data}</script>"#).into())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct CurrentArticleProps {
+    /// Id of the article being read.
+    pub current: String,
+}
+
+#[function_component(ArticleNeighbours)]
+pub fn article_neighbours(props: &CurrentArticleProps) -> Html {
+    let (older, newer) = crate::utils::get_adjacent_articles(&props.current);
+
+    let link = |article: Option<crate::utils::Article>, label: &'static str, align: &'static str| {
+        match article {
+            Some(article) => html! {
+                <Link<Route> to={article.route()} classes={classes!("group", "flex", "flex-col", align)}>
+                    <span class="text-subtext1 font-bold uppercase text-xs tracking-widest">{ label }</span>
+                    <span class="font-bold group-hover:underline">{ &article.matter.title }</span>
+                </Link<Route>>
+            },
+            None => html! { <span></span> },
+        }
+    };
+
+    html! {
+        <nav class="flex justify-between gap-4 mt-16 pt-8 border-t border-surface1" aria-label="More articles">
+            { link(older, "← Previous", "text-left") }
+            { link(newer, "Next →", "text-right") }
+        </nav>
+    }
+}
+
+// Articles that `[[wiki link]]` to this one
+#[function_component(Backlinks)]
+pub fn backlinks(props: &CurrentArticleProps) -> Html {
+    let backlinks = crate::utils::get_backlinks(&props.current);
+
+    if backlinks.is_empty() {
+        return html!();
+    }
+
+    html! {
+        <section class="mt-12" aria-label="Backlinks">
+            <h3 class="text-subtext1 font-bold mb-4 uppercase text-xs tracking-widest">{"Linked from"}</h3>
+            <ul>
+                { for backlinks.into_iter().map(|article| html! {
+                    <li class="border-t border-latte-text dark:border-mocha-text py-2">
+                        <Link<Route> to={article.route()} classes="py-2 flex flex-col group">
+                            <span class="font-bold group-hover:underline">{ &article.matter.title }</span>
+                            <span>{ &article.matter.snippet }</span>
+                        </Link<Route>>
+                    </li>
+                })}
+            </ul>
+        </section>
+    }
+}
+
+#[function_component(RelatedPosts)]
+pub fn related_posts(props: &CurrentArticleProps) -> Html {
+    let related = crate::utils::get_related_articles(&props.current, 3);
+
+    if related.is_empty() {
+        return html!();
+    }
+
+    html! {
+        <section class="mt-12">
+            <h3 class="text-subtext1 font-bold mb-4 uppercase text-xs tracking-widest">{"Related posts"}</h3>
+            <ul>
+                { for related.into_iter().map(|article| html! {
+                    <li class="border-t border-latte-text dark:border-mocha-text py-2">
+                        <Link<Route> to={article.route()} classes="py-2 flex flex-col group">
+                            <span class="font-bold group-hover:underline">{ &article.matter.title }</span>
+                            <span>{ &article.matter.snippet }</span>
+                        </Link<Route>>
+                    </li>
+                })}
+            </ul>
+        </section>
+    }
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TocProps {
     pub toc_items: Vec<TocItem>,
@@ -281,34 +886,135 @@ pub struct TocProps {
 
 #[function_component(TableOfContents)]
 pub fn table_of_contents(props: &TocProps) -> Html {
+    let active = use_state(|| None::<String>);
+
+    // Scroll-spy. Effects never run during SSR, so the server (and a browser
+    // without JS) just gets the static list.
+    {
+        let active = active.clone();
+        let mut ids = Vec::new();
+        toc_ids(&props.toc_items, &mut ids);
+
+        use_effect_with(ids, move |ids| {
+            let observer = observe_headings(ids, move |id| active.set(Some(id)));
+            move || {
+                if let Some((observer, _callback)) = observer {
+                    observer.disconnect();
+                }
+            }
+        });
+    }
+
     html! {
             <nav class="toc-container p-4 bg-transparent">
                 <h3 class="text-subtext1 font-bold mb-4 uppercase text-xs tracking-widest">{"On this page"}</h3>
-                <ul class="space-y-2 list-none border-l border-surface1 ml-2">
-                    { for props.toc_items.iter().map(|item| {
-                        // Calculate indentation based on level (H1 = 0, H2 = 4, H3 = 8...)
-                        // Note: In Tailwind, dynamic strings like ml-{x} must be in your safelist
-                        // or handled via style if the value is truly dynamic.
-                        let left_padding = format!("padding-left: {}rem", (item.level as f32 - 1.0) * 0.75);
-
-                        html! {
-                            <li key={item.id.clone()} style={left_padding}>
-                                <a href={format!("#{}", item.id)}
-       class="block py-1 text-subtext0 hover:text-just-red transition-all duration-200 text-sm border-l-2 border-transparent hover:border-just-red pl-2 -ml-[1px]">
-        { &item.text }
-    </a>
-                            </li>
-                        }
-                    })}
-                </ul>
+                { toc_list(&props.toc_items, true, active.as_deref()) }
             </nav>
         }
 }
 
+// Nested `<ul>`s take care of the indentation, one level per subsection
+fn toc_list(items: &[TocItem], top: bool, active: Option<&str>) -> Html {
+    let class = if top {
+        "space-y-2 list-none border-l border-surface1 ml-2"
+    } else {
+        "space-y-2 list-none pl-3 mt-2"
+    };
+
+    html! {
+        <ul class={class}>
+            { for items.iter().map(|item| html! {
+                <li key={item.id.clone()}>
+                    <a href={item.href()}
+                       aria-current={(active == Some(item.id.as_str())).then_some("location")}
+                       class="block py-1 text-subtext0 hover:text-just-red aria-[current]:text-just-red transition-all duration-200 text-sm border-l-2 border-transparent hover:border-just-red aria-[current]:border-just-red pl-2 -ml-[1px]">
+                        { &item.text }
+                    </a>
+                    if !item.children.is_empty() {
+                        { toc_list(&item.children, false, active) }
+                    }
+                </li>
+            })}
+        </ul>
+    }
+}
+
+fn toc_ids(items: &[TocItem], ids: &mut Vec<String>) {
+    for item in items {
+        ids.push(item.id.clone());
+        toc_ids(&item.children, ids);
+    }
+}
+
+type HeadingObserver = (
+    web_sys::IntersectionObserver,
+    Closure<dyn FnMut(js_sys::Array)>,
+);
+
+// Watches the headings behind `ids` and reports the topmost one entering the
+// upper part of the viewport. The URL hash follows along via `replaceState`,
+// so it doesn't pile up history entries. The closure has to outlive the
+// observer, hence it is returned alongside it.
+fn observe_headings(ids: &[String], on_active: impl Fn(String) + 'static) -> Option<HeadingObserver> {
+    let window = web_sys::window()?;
+    let document = window.document()?;
+
+    let callback = Closure::<dyn FnMut(js_sys::Array)>::new(move |entries: js_sys::Array| {
+        // Entries come in no particular order, the topmost heading wins
+        let visible = entries
+            .iter()
+            .filter_map(|entry| entry.dyn_into::<web_sys::IntersectionObserverEntry>().ok())
+            .filter(|entry| entry.is_intersecting())
+            .min_by(|a, b| {
+                let top =
+                    |entry: &web_sys::IntersectionObserverEntry| entry.bounding_client_rect().top();
+                top(a).total_cmp(&top(b))
+            });
+
+        // comrak puts the id on the `a.anchor` inside the heading
+        let Some(id) = visible
+            .and_then(|entry| entry.target().query_selector("a[id]").ok().flatten())
+            .map(|anchor| anchor.id())
+        else {
+            return;
+        };
+
+        if let Ok(history) = window.history() {
+            let state = history.state().unwrap_or(JsValue::NULL);
+            let _ = history.replace_state_with_url(&state, "", Some(&format!("#{id}")));
+        }
+        on_active(id);
+    });
+
+    let options = web_sys::IntersectionObserverInit::new();
+    // Only the top fifth of the viewport counts as "reading"
+    options.set_root_margin("0px 0px -80% 0px");
+
+    let observer = web_sys::IntersectionObserver::new_with_options(
+        callback.as_ref().unchecked_ref(),
+        &options,
+    )
+    .ok()?;
+
+    for id in ids {
+        if let Some(heading) = document
+            .get_element_by_id(id)
+            .and_then(|anchor| anchor.parent_element())
+        {
+            observer.observe(&heading);
+        }
+    }
+
+    Some((observer, callback))
+}
+
 #[derive(Properties, PartialEq)]
 pub struct TagCloudProps {
     #[prop_or_default]
     pub on_tag_click: Callback<String>,
+    /// Tag currently selected through `?tag=`, if any.
+    #[prop_or_default]
+    pub active: Option<String>,
 }
 
 #[function_component(TagCloud)]
@@ -319,7 +1025,7 @@ pub fn tag_cloud(props: &TagCloudProps) -> Html {
     let mut tags: Vec<_> = tags_map.into_iter().collect();
     
     // Sort ascending (A-Z)
-    tags.sort_by(|a, b| a.0.to_lowercase().cmp(&b.0.to_lowercase()));
+    tags.sort_by_key(|(tag, _)| tag.to_lowercase());
 
     html! {
         <div class="pb-4 mt-8">
@@ -332,10 +1038,15 @@ pub fn tag_cloud(props: &TagCloudProps) -> Html {
                         let name = tag_name.clone();
                         Callback::from(move |_| cb.emit(name.clone()))
                     };
+                    let is_active = props
+                        .active
+                        .as_ref()
+                        .is_some_and(|active| active.eq_ignore_ascii_case(&tag_name));
 
                     html! {
                         <span onclick={on_click}
-                              class="px-3 py-1 bg-surface0 text-blue rounded-full text-xs border border-surface1 hover:border-blue cursor-pointer transition-all active:scale-95">
+                              aria-current={is_active.then_some("true")}
+                              class="px-3 py-1 bg-surface0 text-blue rounded-full text-xs border border-surface1 hover:border-blue aria-[current]:border-blue cursor-pointer transition-all active:scale-95">
                             { format!("{} ({})", tag_name, posts.len()) }
                         </span>
                     }
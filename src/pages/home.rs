@@ -1,6 +1,7 @@
 use yew::prelude::*;
+use yew_router::prelude::Link;
 
-use crate::NAME;
+use crate::{NAME, Route};
 use crate::components::footer::Footer;
 use crate::components::header::Header;
 
@@ -40,6 +41,9 @@ pub fn home_page() -> Html {
                })
              }
           </ul>
+          <Link<Route> to={Route::ArticlesRoute} classes="block my-4 font-bold hover:text-just-red">
+            { "All articles →" }
+          </Link<Route>>
           <div class="border-b broder-latte-text dark:border-mocha-text"></div>
           <Footer />
           </div>
@@ -1,3 +1,4 @@
 pub mod _404;
 pub mod articles;
 pub mod home;
+pub mod series;
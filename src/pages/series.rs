@@ -0,0 +1,115 @@
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::Route;
+use crate::utils::{get_date, get_series};
+
+#[derive(Properties, PartialEq)]
+pub struct SeriesPageProps {
+    pub slug: String,
+}
+
+// https://abhinandhs.deno.dev/series/:slug
+//                                    ^
+//                                    this page
+#[function_component(SeriesPage)]
+pub fn series_page(props: &SeriesPageProps) -> Html {
+    let Some(series) = get_series(&props.slug) else {
+        return html! { <crate::pages::_404::NotFound /> };
+    };
+
+    html! {
+      <>
+        <crate::components::header::Header />
+        <div class="p-4 mx-auto max-w-3xl flex flex-col justify-center">
+          <p class="font-bold mt-12 text-mocha-overlay2">{ "Series" }</p>
+          <h1 class="font-bold text-5xl mt-2">
+            { &series.name }<span class="text-just-red">{ "." }</span>
+          </h1>
+          <p class="mt-2 text-subtext0">{ format!("{} parts", series.parts.len()) }</p>
+
+          <ol class="mt-8">
+            { for series.parts.iter().enumerate().map(|(idx, article)| html! {
+                <li class="border-t border-latte-text dark:border-mocha-text py-2">
+                  <Link<Route> to={article.route()} classes="py-2 flex group gap-4">
+                    <div class="w-24 shrink-0">{ format!("Part {}", idx + 1) }</div>
+                    <div>
+                      <h2 class="font-bold group-hover:underline">{ &article.matter.title }</h2>
+                      <p>{ get_date(&article.matter.published_at, false) }</p>
+                    </div>
+                  </Link<Route>>
+                </li>
+            })}
+          </ol>
+
+          <div class="border-b border-surface1"></div>
+          <crate::components::footer::Footer />
+        </div>
+      </>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SeriesNavProps {
+    /// Series name as written in the front matter.
+    pub series: String,
+    /// Id of the article being read.
+    pub current: String,
+}
+
+/// Box listing every part of a series, with the current one highlighted
+/// and prev/next-in-series links.
+#[function_component(SeriesNav)]
+pub fn series_nav(props: &SeriesNavProps) -> Html {
+    let Some(series) = get_series(&crate::utils::slugify(&props.series)) else {
+        return html!();
+    };
+    let Some(current) = series.position(&props.current) else {
+        return html!();
+    };
+
+    let prev = current.checked_sub(1).and_then(|i| series.parts.get(i));
+    let next = series.parts.get(current + 1);
+
+    html! {
+        <nav class="series-nav my-8 p-4 rounded-md border border-surface1 bg-latte-base dark:bg-mocha-base" aria-label="Series">
+            <p class="text-subtext1 font-bold uppercase text-xs tracking-widest">
+                { format!("Part {} of {} in ", current + 1, series.parts.len()) }
+                <Link<Route> to={Route::Series { slug: series.slug.clone() }} classes="text-just-red">
+                    { &series.name }
+                </Link<Route>>
+            </p>
+            <ol class="mt-4 space-y-1 list-decimal list-inside">
+                { for series.parts.iter().enumerate().map(|(idx, article)| {
+                    if idx == current {
+                        html! {
+                            <li class="font-bold text-just-red" aria-current="true">{ &article.matter.title }</li>
+                        }
+                    } else {
+                        html! {
+                            <li>
+                                <Link<Route> to={article.route()} classes="hover:text-just-red">
+                                    { &article.matter.title }
+                                </Link<Route>>
+                            </li>
+                        }
+                    }
+                })}
+            </ol>
+            <div class="flex justify-between mt-4 text-sm font-bold">
+                if let Some(prev) = prev {
+                    <Link<Route> to={prev.route()} classes="hover:text-just-red">
+                        { format!("← {}", prev.matter.title) }
+                    </Link<Route>>
+                } else {
+                    <span></span>
+                }
+                if let Some(next) = next {
+                    <Link<Route> to={next.route()} classes="hover:text-just-red">
+                        { format!("{} →", next.matter.title) }
+                    </Link<Route>>
+                }
+            </div>
+        </nav>
+    }
+}
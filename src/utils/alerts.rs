@@ -0,0 +1,180 @@
+// GitHub-style alerts, `> [!NOTE]`, rendered as a colored box with an icon
+// and a title. comrak parses GitHub's five types itself, the other types in
+// `ALERTS` are picked up from blockquotes starting with `[!TYPE]`. An
+// optional title follows the marker on the same line: `> [!TIP] Shortcut`.
+//
+// Shared with the feed generator, which includes this file by path and
+// renders plain labeled blockquotes instead.
+
+use comrak::nodes::{AstNode, NodeValue};
+
+pub struct AlertKind {
+    /// Lowercase marker, also the `markdown-alert-{name}` class.
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Inner markup of the 24x24 SVG icon.
+    pub icon: &'static str,
+}
+
+// Feather icons, like the rest of the site
+#[rustfmt::skip]
+pub const ALERTS: &[AlertKind] = &[
+    // GitHub's
+    AlertKind {
+        name: "note",
+        title: "Note",
+        icon: r#"<circle cx="12" cy="12" r="10"></circle><line x1="12" y1="16" x2="12" y2="12"></line><line x1="12" y1="8" x2="12.01" y2="8"></line>"#,
+    },
+    AlertKind {
+        name: "tip",
+        title: "Tip",
+        icon: r#"<polygon points="13 2 3 14 12 14 11 22 21 10 12 10 13 2"></polygon>"#,
+    },
+    AlertKind {
+        name: "important",
+        title: "Important",
+        icon: r#"<path d="M21 15a2 2 0 0 1-2 2H7l-4 4V5a2 2 0 0 1 2-2h14a2 2 0 0 1 2 2z"></path><line x1="12" y1="7" x2="12" y2="10"></line><line x1="12" y1="13" x2="12.01" y2="13"></line>"#,
+    },
+    AlertKind {
+        name: "warning",
+        title: "Warning",
+        icon: r#"<path d="M10.29 3.86L1.82 18a2 2 0 0 0 1.71 3h16.94a2 2 0 0 0 1.71-3L13.71 3.86a2 2 0 0 0-3.42 0z"></path><line x1="12" y1="9" x2="12" y2="13"></line><line x1="12" y1="17" x2="12.01" y2="17"></line>"#,
+    },
+    AlertKind {
+        name: "caution",
+        title: "Caution",
+        icon: r#"<polygon points="7.86 2 16.14 2 22 7.86 22 16.14 16.14 22 7.86 22 2 16.14 2 7.86 7.86 2"></polygon><line x1="12" y1="8" x2="12" y2="12"></line><line x1="12" y1="16" x2="12.01" y2="16"></line>"#,
+    },
+    // Ours
+    AlertKind {
+        name: "example",
+        title: "Example",
+        icon: r#"<polyline points="16 18 22 12 16 6"></polyline><polyline points="8 6 2 12 8 18"></polyline>"#,
+    },
+    AlertKind {
+        name: "question",
+        title: "Question",
+        icon: r#"<circle cx="12" cy="12" r="10"></circle><path d="M9.09 9a3 3 0 0 1 5.83 1c0 2-3 3-3 3"></path><line x1="12" y1="17" x2="12.01" y2="17"></line>"#,
+    },
+];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Style {
+    /// Colored box with an icon, styled by `input.css`.
+    Site,
+    /// `<blockquote>` with a bold title, for feed readers.
+    Feed,
+}
+
+pub fn lookup(name: &str) -> Option<&'static AlertKind> {
+    ALERTS
+        .iter()
+        .find(|kind| kind.name.eq_ignore_ascii_case(name))
+}
+
+/// `[!TYPE]` or `[!TYPE] Some title` -> the type and the title.
+pub fn parse_marker(line: &str) -> Option<(&'static AlertKind, Option<&str>)> {
+    let (name, title) = line.strip_prefix("[!")?.split_once(']')?;
+    let title = Some(title.trim()).filter(|title| !title.is_empty());
+    Some((lookup(name)?, title))
+}
+
+/// Replaces every alert under `root` with `style` markup around its
+/// content. `alloc` puts a new node in the document's arena.
+pub fn render_alerts<'a>(
+    root: &'a AstNode<'a>,
+    style: Style,
+    alloc: impl Fn(NodeValue) -> &'a AstNode<'a>,
+) {
+    let candidates = root
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.data.borrow().value,
+                NodeValue::Alert(_) | NodeValue::BlockQuote
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for node in candidates {
+        let alert = match &node.data.borrow().value {
+            NodeValue::Alert(alert) => {
+                lookup(alert.alert_type.default_title()).map(|kind| (kind, alert.title.clone()))
+            }
+            _ => None,
+        };
+        let Some((kind, title)) = alert.or_else(|| custom_alert(node)) else {
+            continue;
+        };
+
+        let title = escape(title.as_deref().unwrap_or(kind.title));
+        let (open, close) = match style {
+            Style::Site => (
+                format!(
+                    concat!(
+                        r#"<div class="markdown-alert markdown-alert-{}" role="note">"#,
+                        r#"<p class="markdown-alert-title">"#,
+                        r#"<svg aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">{}</svg>"#,
+                        "{}</p>",
+                    ),
+                    kind.name, kind.icon, title
+                ),
+                "</div>",
+            ),
+            Style::Feed => (
+                format!("<blockquote><p><strong>{}</strong></p>", title),
+                "</blockquote>",
+            ),
+        };
+
+        node.insert_before(alloc(NodeValue::Raw(open)));
+        while let Some(child) = node.first_child() {
+            node.insert_before(child);
+        }
+        node.data.borrow_mut().value = NodeValue::Raw(close.to_string());
+    }
+}
+
+// A blockquote whose first line is `[!TYPE]` with a type comrak doesn't
+// know. The marker line is removed from the content.
+fn custom_alert<'a>(node: &'a AstNode<'a>) -> Option<(&'static AlertKind, Option<String>)> {
+    let paragraph = node.first_child()?;
+    if !matches!(paragraph.data.borrow().value, NodeValue::Paragraph) {
+        return None;
+    }
+
+    // `[` and `]` that don't make a link come out as text nodes of their own
+    let mut line = String::new();
+    let mut marker = Vec::new();
+    for child in paragraph.children() {
+        match &child.data.borrow().value {
+            NodeValue::Text(text) => line.push_str(text),
+            NodeValue::SoftBreak | NodeValue::LineBreak => {
+                marker.push(child);
+                break;
+            }
+            _ => return None,
+        }
+        marker.push(child);
+    }
+
+    let (kind, title) = parse_marker(line.trim())?;
+    let title = title.map(str::to_string);
+
+    for child in marker {
+        child.detach();
+    }
+    if paragraph.first_child().is_none() {
+        paragraph.detach();
+    }
+
+    Some((kind, title))
+}
+
+fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
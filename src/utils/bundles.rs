@@ -0,0 +1,104 @@
+// Articles are either a single `articles/published/<id>.md` or a bundle
+// directory, `articles/published/<id>/index.md`, next to its images and
+// attachments. Bundle assets are copied to `static/bundles/<id>/` and
+// relative links in the post are rewritten to point there.
+//
+// Std only: build.rs and the feed generator include this file by path.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Where bundle assets are published, relative to the site root.
+pub const BUNDLES_URL: &str = "/static/bundles";
+pub const BUNDLES_DIR: &str = "static/bundles";
+pub const BUNDLE_INDEX: &str = "index.md";
+
+pub struct ArticleSource {
+    pub id: String,
+    /// The `.md` file, `index.md` for bundles.
+    pub markdown: PathBuf,
+    /// The bundle directory, if the article is one.
+    pub bundle: Option<PathBuf>,
+}
+
+/// Every article under `dir`, sorted by path.
+pub fn discover(dir: &Path) -> Vec<ArticleSource> {
+    let mut entries = fs::read_dir(dir)
+        .expect("Failed to read articles directory")
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .collect::<Vec<_>>();
+
+    entries.sort();
+
+    entries
+        .into_iter()
+        .filter_map(|path| {
+            if path.is_dir() {
+                let id = path.file_name()?.to_string_lossy().to_string();
+                let markdown = path.join(BUNDLE_INDEX);
+                markdown.is_file().then_some(ArticleSource {
+                    id,
+                    markdown,
+                    bundle: Some(path),
+                })
+            } else if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                let id = path.file_stem()?.to_string_lossy().to_string();
+                Some(ArticleSource {
+                    id,
+                    markdown: path,
+                    bundle: None,
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Copies everything in `bundle` but the markdown to `out`, keeping the
+/// layout. Unchanged files are left alone.
+pub fn copy_assets(bundle: &Path, out: &Path) {
+    let entries = fs::read_dir(bundle).expect("Failed to read article bundle");
+
+    for entry in entries.filter_map(Result::ok) {
+        let path = entry.path();
+        let target = out.join(entry.file_name());
+
+        if path.is_dir() {
+            copy_assets(&path, &target);
+        } else if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+            if matches!((modified(&target), modified(&path)), (Some(t), Some(s)) if t >= s) {
+                continue;
+            }
+
+            fs::create_dir_all(out).expect("Failed to create bundle output directory");
+            fs::copy(&path, &target)
+                .unwrap_or_else(|err| panic!("Failed to copy {}: {err}", path.display()));
+        }
+    }
+}
+
+/// `diagram.png` / `./files/a.pdf` -> `{base}/diagram.png` / `{base}/files/a.pdf`.
+/// Absolute paths, fragments and anything with a scheme give `None`.
+pub fn resolve(url: &str, base: &str) -> Option<String> {
+    let has_scheme = url.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+
+    if url.is_empty() || url.starts_with(['/', '#', '?']) || has_scheme {
+        return None;
+    }
+
+    Some(format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        url.trim_start_matches("./")
+    ))
+}
@@ -0,0 +1,231 @@
+// Fenced code blocks: info string attributes, per-line highlighting and the
+// header bar with file name, language, copy button and, for `playground`
+// snippets, a Run button.
+
+use std::ops::RangeInclusive;
+
+use syntect::html::{ClassStyle, line_tokens_to_classed_spans};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Parsed fence info string, e.g. `rust,playground title="main.rs" {3,5-7} showLineNumbers`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FenceInfo {
+    pub lang: Option<String>,
+    pub title: Option<String>,
+    /// 1-based, inclusive line ranges to highlight.
+    pub highlight: Vec<RangeInclusive<usize>>,
+    pub line_numbers: bool,
+    /// Rust only: a Run button sends the code to `crate::PLAYGROUND_URL`.
+    pub playground: bool,
+}
+
+impl FenceInfo {
+    pub fn parse(info: &str) -> Self {
+        let mut fence = FenceInfo::default();
+
+        for (idx, token) in tokenize(info).into_iter().enumerate() {
+            if let Some(title) = token.strip_prefix("title=") {
+                fence.title = Some(title.trim_matches('"').to_string());
+            } else if let Some(ranges) = token.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+                fence.highlight.extend(ranges.split(',').filter_map(parse_range));
+            } else if token == "showLineNumbers" {
+                fence.line_numbers = true;
+            } else if token == "playground" {
+                fence.playground = true;
+            } else if idx == 0 {
+                // mdBook-style attributes after the language, `rust,playground`
+                let mut parts = token.split(',');
+                fence.lang = parts
+                    .next()
+                    .filter(|lang| !lang.is_empty())
+                    .map(str::to_string);
+                fence.playground |= parts.any(|attribute| attribute == "playground");
+            }
+        }
+        fence
+    }
+
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight.iter().any(|range| range.contains(&line))
+    }
+}
+
+// Splits on whitespace, except inside double quotes
+fn tokenize(info: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in info.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+// `3` or `5-7`
+fn parse_range(range: &str) -> Option<RangeInclusive<usize>> {
+    match range.trim().split_once('-') {
+        Some((start, end)) => Some(start.trim().parse().ok()?..=end.trim().parse().ok()?),
+        None => {
+            let line = range.trim().parse().ok()?;
+            Some(line..=line)
+        }
+    }
+}
+
+pub fn escape_html(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn open_span(classes: &str) -> String {
+    format!(r#"<span class="{classes}">"#)
+}
+
+/// Renders a code block as a `.code-block` wrapper holding a header bar and
+/// a `<pre>` with one `.code-line` span per line, highlighted with the same
+/// spaced syntect classes `static/syntax.css` is generated for.
+pub fn render_code_block(info: &FenceInfo, code: &str, syntax_set: &SyntaxSet) -> String {
+    let syntax = info
+        .lang
+        .as_deref()
+        .and_then(|lang| syntax_set.find_syntax_by_token(lang))
+        .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+
+    let mut parse_state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut lines = String::new();
+
+    for (idx, line) in LinesWithEndings::from(code).enumerate() {
+        let number = idx + 1;
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        // Scopes still open from previous lines are re-opened, so every
+        // line is a self-contained span that can be highlighted on its own
+        let reopened: String = stack
+            .as_slice()
+            .iter()
+            .map(|scope| open_span(&scope.build_string().replace('.', " ")))
+            .collect();
+
+        // The newline is kept out of the line span, ops pointing past the
+        // content are clamped onto its end
+        let body = parse_state
+            .parse_line(line, syntax_set)
+            .ok()
+            .and_then(|ops| {
+                let ops: Vec<_> = ops
+                    .into_iter()
+                    .map(|(i, op)| (i.min(content.len()), op))
+                    .collect();
+                line_tokens_to_classed_spans(content, &ops, ClassStyle::Spaced, &mut stack).ok()
+            })
+            .map(|(html, _)| format!("{reopened}{html}{}", "</span>".repeat(stack.len())))
+            .unwrap_or_else(|| escape_html(content));
+
+        let class = if info.is_highlighted(number) {
+            "code-line highlighted"
+        } else {
+            "code-line"
+        };
+        lines.push_str(&format!(
+            r#"<span class="{class}" data-line="{number}">{body}</span>"#
+        ));
+        lines.push('\n');
+    }
+
+    let lang = info.lang.as_deref().map(escape_html);
+    let title = info
+        .title
+        .as_deref()
+        .map(|t| format!(r#"<span class="code-title">{}</span>"#, escape_html(t)))
+        .unwrap_or_default();
+    let lang_label = lang
+        .as_deref()
+        .map(|l| format!(r#"<span class="code-lang">{l}</span>"#))
+        .unwrap_or_default();
+    let code_class = lang
+        .as_deref()
+        .map(|l| format!(r#" class="language-{l}""#))
+        .unwrap_or_default();
+    // The output appears under the code once the page has hydrated, see
+    // `run_on_playground` in `pages::articles`
+    let run = if info.playground && info.lang.as_deref() == Some("rust") {
+        r#"<button type="button" class="code-run" aria-label="Run code">Run</button>"#
+    } else {
+        ""
+    };
+    let line_numbers = if info.line_numbers {
+        " data-line-numbers"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"<div class="code-block"><div class="code-header">{title}{lang_label}<button type="button" class="code-copy" aria-label="Copy code">Copy</button>{run}</div><pre class="syntax-highlighting"{line_numbers}><code{code_class}>{lines}</code></pre></div>
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fence_info() {
+        assert_eq!(
+            FenceInfo::parse(r#"rust,playground {1,3-5} title="x""#),
+            FenceInfo {
+                lang: Some("rust".to_string()),
+                title: Some("x".to_string()),
+                highlight: vec![1..=1, 3..=5],
+                line_numbers: false,
+                playground: true,
+            }
+        );
+    }
+
+    #[test]
+    fn fence_info_attributes_in_any_order() {
+        let fence = FenceInfo::parse(r#"toml title="Cargo file.toml" showLineNumbers {2} {4-6}"#);
+        assert_eq!(fence.lang.as_deref(), Some("toml"));
+        assert_eq!(fence.title.as_deref(), Some("Cargo file.toml"));
+        assert_eq!(fence.highlight, [2..=2, 4..=6]);
+        assert!(fence.line_numbers);
+        assert!(!fence.playground);
+        assert!(fence.is_highlighted(5) && !fence.is_highlighted(3));
+    }
+
+    #[test]
+    fn fence_info_without_lang() {
+        assert_eq!(FenceInfo::parse(""), FenceInfo::default());
+        let fence = FenceInfo::parse(r#"title="notes.txt""#);
+        assert_eq!(fence.lang, None);
+        assert_eq!(fence.title.as_deref(), Some("notes.txt"));
+    }
+}
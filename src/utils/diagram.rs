@@ -0,0 +1,305 @@
+// Build-time diagrams: ```` ```dot ```` and ```` ```mermaid ```` fences are
+// laid out with layout-rs and inlined as SVG, so nothing runs in the browser
+// and no external `dot`/`mmdc` process is needed.
+//
+// Only build.rs includes this file, by path; the SVGs end up in a table that
+// `utils::diagrams` looks fences up in, and layout-rs stays out of the wasm.
+//
+// Only Mermaid flowcharts (`graph` / `flowchart`) are supported; they are
+// translated to dot and go through the same layout engine. Anything else
+// returns an error and the fence stays a plain code block.
+
+use layout::backends::svg::SVGWriter;
+use layout::gv::{DotParser, GraphBuilder};
+
+use super::code::escape_html;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagramKind {
+    Dot,
+    Mermaid,
+}
+
+impl DiagramKind {
+    pub fn from_lang(lang: &str) -> Option<Self> {
+        match lang {
+            "dot" | "graphviz" => Some(Self::Dot),
+            "mermaid" => Some(Self::Mermaid),
+            _ => None,
+        }
+    }
+}
+
+/// Renders a diagram fence to a `<figure class="diagram">`. `index` keeps the
+/// SVG ids (arrow markers, edge paths) unique across diagrams.
+pub fn render_diagram(
+    kind: DiagramKind,
+    source: &str,
+    title: Option<&str>,
+    index: usize,
+) -> Result<String, String> {
+    let dot = match kind {
+        DiagramKind::Dot => source.to_string(),
+        DiagramKind::Mermaid => mermaid_to_dot(source)?,
+    };
+
+    let svg = dot_to_svg(&dot)?;
+    let svg = prefix_ids(strip_prolog(&svg), &format!("diagram-{}", index));
+
+    let label = title
+        .map(|title| format!(r#" role="img" aria-label="{}""#, escape_html(title)))
+        .unwrap_or_default();
+    let caption = title
+        .map(|title| format!("<figcaption>{}</figcaption>", escape_html(title)))
+        .unwrap_or_default();
+
+    Ok(format!(
+        r#"<figure class="diagram"{}>{}{}</figure>"#,
+        label, svg, caption
+    ))
+}
+
+fn dot_to_svg(dot: &str) -> Result<String, String> {
+    let mut parser = DotParser::new(dot);
+    let graph = parser
+        .process()
+        .map_err(|err| format!("invalid dot: {}", err))?;
+
+    let mut builder = GraphBuilder::new();
+    builder.visit_graph(&graph);
+    let mut visual = builder.get();
+
+    if visual.num_nodes() == 0 {
+        return Err("diagram has no nodes".to_string());
+    }
+
+    let mut svg = SVGWriter::new();
+    visual.do_it(false, false, false, &mut svg);
+    Ok(svg.finalize())
+}
+
+// The writer emits a standalone document; inline SVG can't carry the prolog.
+fn strip_prolog(svg: &str) -> &str {
+    match svg.find("<svg") {
+        Some(start) => &svg[start..],
+        None => svg,
+    }
+}
+
+fn prefix_ids(svg: &str, prefix: &str) -> String {
+    svg.replace(r#"id=""#, &format!(r#"id="{}-"#, prefix))
+        .replace("url(#", &format!("url(#{}-", prefix))
+        .replace(r##"href="#"##, &format!(r##"href="#{}-"##, prefix))
+}
+
+// Mermaid flowchart subset -> dot.
+//
+// Supported: a `graph`/`flowchart` header with a direction, `;` or newline
+// separated statements, `%%` comments, chained edges (`a --> b --> c`),
+// `-->`, `---`, `-.->`, `==>` links with `|label|` or `-- label -->` text,
+// and the `[box]`, `(rounded)`, `([stadium])`, `((circle))`, `{rhombus}`
+// node shapes (rhombi are drawn as boxes).
+fn mermaid_to_dot(source: &str) -> Result<String, String> {
+    let mut statements = source
+        .lines()
+        .map(|line| line.split("%%").next().unwrap_or_default())
+        .flat_map(|line| line.split(';'))
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty());
+
+    let header = statements.next().ok_or("empty mermaid diagram")?;
+    let mut words = header.split_whitespace();
+    match words.next() {
+        Some("graph" | "flowchart") => (),
+        Some(other) => {
+            return Err(format!(
+                "unsupported mermaid diagram `{}`, only flowcharts render",
+                other
+            ));
+        }
+        None => return Err("empty mermaid diagram".to_string()),
+    }
+    let rankdir = match words.next() {
+        Some("LR" | "RL") => "LR",
+        _ => "TB",
+    };
+
+    let mut dot = format!("digraph {{\n  rankdir={};\n", rankdir);
+    for statement in statements {
+        mermaid_statement(statement, &mut dot)?;
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+struct Link {
+    label: Option<String>,
+    dashed: bool,
+    arrow: bool,
+}
+
+fn mermaid_statement(statement: &str, dot: &mut String) -> Result<(), String> {
+    // Styling directives have no equivalent in the layout engine
+    let keyword = statement.split_whitespace().next().unwrap_or_default();
+    if [
+        "style",
+        "classDef",
+        "class",
+        "click",
+        "linkStyle",
+        "subgraph",
+        "end",
+        "direction",
+    ]
+    .contains(&keyword)
+    {
+        return Ok(());
+    }
+
+    let mut rest = statement;
+    let mut previous = mermaid_nodes(&mut rest, dot)?;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(());
+        }
+
+        let link = mermaid_link(&mut rest)
+            .ok_or_else(|| format!("unexpected `{}` in mermaid statement", rest))?;
+        rest = rest.trim_start();
+        let next = mermaid_nodes(&mut rest, dot)?;
+
+        let mut attrs = Vec::new();
+        if let Some(label) = &link.label {
+            attrs.push(format!("label={}", dot_quote(label)));
+        }
+        if link.dashed {
+            attrs.push("style=dashed".to_string());
+        }
+        let op = if link.arrow { "->" } else { "--" };
+        for from in &previous {
+            for to in &next {
+                dot.push_str(&format!(
+                    "  {} {} {} [{}];\n",
+                    dot_quote(from),
+                    op,
+                    dot_quote(to),
+                    attrs.join(", ")
+                ));
+            }
+        }
+
+        previous = next;
+    }
+}
+
+// Parses `a & b & c` fan-out groups.
+fn mermaid_nodes(rest: &mut &str, dot: &mut String) -> Result<Vec<String>, String> {
+    let mut nodes = vec![mermaid_node(rest, dot)?];
+    while let Some(next) = rest.trim_start().strip_prefix('&') {
+        *rest = next.trim_start();
+        nodes.push(mermaid_node(rest, dot)?);
+    }
+    Ok(nodes)
+}
+
+// Parses `id` or `id<shape>label<shape>`, declaring the node if it carries a
+// label. Returns the node id.
+fn mermaid_node(rest: &mut &str, dot: &mut String) -> Result<String, String> {
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .unwrap_or(rest.len());
+    if end == 0 {
+        return Err(format!("expected a node id at `{}`", rest));
+    }
+    let id = rest[..end].to_string();
+    *rest = &rest[end..];
+
+    const SHAPES: [(&str, &str, &str); 7] = [
+        ("((", "))", "circle"),
+        ("([", "])", "Mrecord"),
+        ("[(", ")]", "Mrecord"),
+        ("[[", "]]", "box"),
+        ("[", "]", "box"),
+        ("(", ")", "Mrecord"),
+        ("{", "}", "box"),
+    ];
+
+    let shape = SHAPES.iter().find(|(open, _, _)| rest.starts_with(open));
+    let Some((open, close, shape)) = shape else {
+        return Ok(id);
+    };
+
+    let body = &rest[open.len()..];
+    let end = body
+        .find(close)
+        .ok_or_else(|| format!("unclosed `{}` on node `{}`", open, id))?;
+    let label = body[..end].trim().trim_matches('"');
+    *rest = &body[end + close.len()..];
+
+    // Records treat `|{}<>` as field syntax
+    let label = if *shape == "Mrecord" {
+        label.replace(['|', '{', '}', '<', '>'], " ")
+    } else {
+        label.to_string()
+    };
+
+    dot.push_str(&format!(
+        "  {} [shape={}, label={}];\n",
+        dot_quote(&id),
+        shape,
+        dot_quote(&label)
+    ));
+    Ok(id)
+}
+
+fn mermaid_link(rest: &mut &str) -> Option<Link> {
+    const LINKS: [(&str, bool, bool); 6] = [
+        ("-.->", true, true),
+        ("-.-", true, false),
+        ("==>", false, true),
+        ("-->", false, true),
+        ("===", false, false),
+        ("---", false, false),
+    ];
+
+    // `a -- text --> b` / `a -. text .-> b` / `a == text ==> b`
+    for (open, close, dashed) in [
+        ("-- ", "-->", false),
+        ("-. ", ".->", true),
+        ("== ", "==>", false),
+    ] {
+        if let Some(body) = rest.strip_prefix(open) {
+            let end = body.find(close)?;
+            let label = body[..end].trim().to_string();
+            *rest = &body[end + close.len()..];
+            return Some(Link {
+                label: Some(label),
+                dashed,
+                arrow: true,
+            });
+        }
+    }
+
+    let (op, dashed, arrow) = LINKS.iter().find(|(op, _, _)| rest.starts_with(op))?;
+    *rest = rest[op.len()..].trim_start();
+
+    // `a -->|text| b`
+    let mut label = None;
+    if let Some(body) = rest.strip_prefix('|') {
+        let end = body.find('|')?;
+        label = Some(body[..end].trim().to_string());
+        *rest = &body[end + 1..];
+    }
+
+    Some(Link {
+        label,
+        dashed: *dashed,
+        arrow: *arrow,
+    })
+}
+
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,20 @@
+// Diagram fences, pre-rendered: build.rs lays out every ```` ```dot ```` and
+// ```` ```mermaid ```` fence of the published articles (see `diagram.rs`)
+// and writes the SVGs to a table keyed by the fence's info string and body.
+
+pub struct Diagram {
+    pub info: &'static str,
+    pub source: &'static str,
+    pub html: &'static str,
+}
+
+include!(concat!(env!("OUT_DIR"), "/diagrams.rs"));
+
+/// The `<figure>` of a diagram fence. `None` for other fences, and for
+/// diagrams build.rs could not lay out, which stay readable as source.
+pub fn lookup(info: &str, source: &str) -> Option<&'static str> {
+    DIAGRAMS
+        .iter()
+        .find(|diagram| diagram.info == info && diagram.source == source)
+        .map(|diagram| diagram.html)
+}
@@ -0,0 +1,99 @@
+// Optional markdown syntax. The site-wide defaults live here; a post can flip
+// any of them with a `markdown:` map in its front matter:
+//
+//     markdown:
+//       smart_punctuation: false
+//
+// Shared with the feed generator, which includes this file by path.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarkdownExtensions {
+    /// `[^note]` references with a notes section and backlinks.
+    pub footnotes: bool,
+    /// GFM pipe tables.
+    pub tables: bool,
+    /// `Term` followed by `: definition` lines.
+    pub description_lists: bool,
+    /// `^sup^`
+    pub superscript: bool,
+    /// `~sub~`; strikethrough then needs `~~double~~` tildes.
+    pub subscript: bool,
+    /// Bare `https://` and `www.` links.
+    pub autolink: bool,
+    /// Curly quotes, en/em dashes and ellipses.
+    pub smart_punctuation: bool,
+    /// Pass raw HTML through and skip sanitization. Trusted posts only.
+    pub raw_html: bool,
+}
+
+pub const SITE_EXTENSIONS: MarkdownExtensions = MarkdownExtensions {
+    footnotes: true,
+    tables: true,
+    description_lists: true,
+    superscript: true,
+    subscript: true,
+    autolink: true,
+    smart_punctuation: true,
+    raw_html: false,
+};
+
+/// Per-post overrides from front matter; unset keys keep the site default.
+#[derive(Default, Deserialize, Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExtensionOverrides {
+    pub footnotes: Option<bool>,
+    pub tables: Option<bool>,
+    pub description_lists: Option<bool>,
+    pub superscript: Option<bool>,
+    pub subscript: Option<bool>,
+    pub autolink: Option<bool>,
+    pub smart_punctuation: Option<bool>,
+    pub raw_html: Option<bool>,
+}
+
+impl MarkdownExtensions {
+    pub fn with_overrides(self, overrides: Option<&ExtensionOverrides>) -> Self {
+        let Some(o) = overrides else {
+            return self;
+        };
+
+        MarkdownExtensions {
+            footnotes: o.footnotes.unwrap_or(self.footnotes),
+            tables: o.tables.unwrap_or(self.tables),
+            description_lists: o.description_lists.unwrap_or(self.description_lists),
+            superscript: o.superscript.unwrap_or(self.superscript),
+            subscript: o.subscript.unwrap_or(self.subscript),
+            autolink: o.autolink.unwrap_or(self.autolink),
+            smart_punctuation: o.smart_punctuation.unwrap_or(self.smart_punctuation),
+            raw_html: o.raw_html.unwrap_or(self.raw_html),
+        }
+    }
+
+    /// Everything the site parses a post with: the syntax every post gets,
+    /// plus these. Heading ids have to come out the same in `markdown_to_html`,
+    /// the reading stats and build.rs, so they all start here.
+    pub fn options(&self) -> comrak::Options<'static> {
+        let mut options = comrak::Options::default();
+        options.extension.strikethrough = true;
+        // Heading ids and their anchors are ours, see `headings`
+        options.extension.alerts = true;
+        options.extension.tasklist = true;
+        options.extension.spoiler = true;
+        options.extension.math_dollars = true;
+        options.extension.math_code = true;
+        self.apply(&mut options);
+        options
+    }
+
+    pub fn apply(&self, options: &mut comrak::Options) {
+        options.extension.footnotes = self.footnotes;
+        options.extension.table = self.tables;
+        options.extension.description_lists = self.description_lists;
+        options.extension.superscript = self.superscript;
+        options.extension.subscript = self.subscript;
+        options.extension.autolink = self.autolink;
+        options.parse.smart = self.smart_punctuation;
+        options.render.r#unsafe = self.raw_html;
+    }
+}
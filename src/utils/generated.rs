@@ -5,3 +5,6 @@ pub const ARTICLES: &[(&str, &str)] = &[
    ("hello-world-02", include_str!("../../articles/published/hello-world-02.md")),
    ("hello-world", include_str!("../../articles/published/hello-world.md")),
 ];
+
+pub const BUNDLES: &[&str] = &[
+];
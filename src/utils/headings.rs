@@ -0,0 +1,79 @@
+// Heading anchors: a link icon in front of every heading pointing at its id,
+// and a button after it that copies the section's URL once the page is
+// hydrated (see `on_markdown_click` in `pages::articles`). Both are hidden
+// until the heading is hovered or focused.
+//
+// The ids themselves come from `heading_ids`, which build.rs also runs to
+// check `[[id#heading]]` links, so the two can't disagree.
+
+use comrak::nodes::{AstNode, NodeValue};
+
+use super::code::escape_html;
+
+/// Prefix of every heading id.
+pub const HEADING_ID_PREFIX: &str = "md-heading-";
+
+// Feather's `link` and `copy`, like the footer icons
+const LINK_ICON: &str = concat!(
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#,
+    r#"<path d="M10 13a5 5 0 0 0 7.54.54l3-3a5 5 0 0 0-7.07-7.07l-1.72 1.71"></path>"#,
+    r#"<path d="M14 11a5 5 0 0 0-7.54-.54l-3 3a5 5 0 0 0 7.07 7.07l1.71-1.71"></path>"#,
+    "</svg>",
+);
+
+const COPY_ICON: &str = concat!(
+    r#"<svg xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#,
+    r#"<rect x="9" y="9" width="13" height="13" rx="2" ry="2"></rect>"#,
+    r#"<path d="M5 15H4a2 2 0 0 1-2-2V4a2 2 0 0 1 2-2h9a2 2 0 0 1 2 2v1"></path>"#,
+    "</svg>",
+);
+
+/// First child of the heading. It carries the heading's `id`, which is what
+/// the TOC scroll-spy looks up (`a[id]`).
+pub fn render_anchor(id: &str) -> String {
+    format!(
+        r##"<a href="#{id}" id="{id}" class="anchor" aria-hidden="true">{LINK_ICON}</a>"##,
+        id = escape_html(id),
+    )
+}
+
+/// Last child of the heading.
+pub fn render_copy_button(id: &str) -> String {
+    format!(
+        r#"<button type="button" class="heading-copy" data-heading="{}" aria-label="Copy link to this section" title="Copy link to this section">{COPY_ICON}</button>"#,
+        escape_html(id),
+    )
+}
+
+/// `(heading, text, id)` of every heading of the document, nested ones
+/// included. A single `Anchorizer` goes through them in document order, like
+/// comrak's own `header_ids`, so ids get the same `-1`/`-2` suffixes for
+/// duplicates.
+pub fn heading_ids<'a>(root: &'a AstNode<'a>) -> Vec<(&'a AstNode<'a>, String, String)> {
+    let mut anchorizer = comrak::Anchorizer::new();
+    root.descendants()
+        .filter(|node| matches!(node.data.borrow().value, NodeValue::Heading(_)))
+        .map(|node| {
+            let mut text = String::new();
+            collect_text(node, &mut text);
+            let id = format!("{HEADING_ID_PREFIX}{}", anchorizer.anchorize(&text));
+            (node, text, id)
+        })
+        .collect()
+}
+
+/// The text of a node and its children. Mirrors what comrak feeds its own
+/// anchorizer, breaks become spaces.
+pub fn collect_text<'a>(node: &'a AstNode<'a>, output: &mut String) {
+    match node.data.borrow().value {
+        NodeValue::Text(ref t) => output.push_str(t),
+        NodeValue::Code(ref c) => output.push_str(&c.literal),
+        NodeValue::Math(ref m) => output.push_str(&m.literal),
+        NodeValue::LineBreak | NodeValue::SoftBreak => output.push(' '),
+        _ => {
+            for child in node.children() {
+                collect_text(child, output);
+            }
+        }
+    }
+}
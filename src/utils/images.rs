@@ -0,0 +1,80 @@
+// Responsive images: build.rs writes WebP/AVIF variants of everything under
+// `static/images` and a manifest of them, markdown images pointing at one
+// of those files become a `<picture>` with both formats.
+
+use super::code::escape_html;
+
+pub struct Image {
+    pub src: &'static str,
+    pub width: u32,
+    pub height: u32,
+    /// Ascending by width.
+    pub variants: &'static [Variant],
+}
+
+/// `None` for a format whose file came out no smaller than the original.
+pub struct Variant {
+    pub width: u32,
+    pub webp: Option<&'static str>,
+    pub avif: Option<&'static str>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/images.rs"));
+
+/// Rendered width of article images: the `max-w-3xl` column, or the viewport.
+const SIZES: &str = "(min-width: 48rem) 48rem, 100vw";
+
+fn lookup(src: &str) -> Option<&'static Image> {
+    let src = src.trim_start_matches('/');
+    IMAGES
+        .iter()
+        .find(|image| image.src.trim_start_matches('/') == src)
+}
+
+pub fn render_image(src: &str, alt: &str, title: &str) -> String {
+    let title = match title {
+        "" => String::new(),
+        title => format!(r#" title="{}""#, escape_html(title)),
+    };
+    let image = lookup(src);
+    let src = escape_html(src);
+    let alt = escape_html(alt);
+
+    let Some(image) = image else {
+        // Not ours (remote, or outside `static/images`): no variants and no
+        // known size, but it can still load lazily
+        return format!(r#"<img src="{src}" alt="{alt}"{title} loading="lazy" decoding="async">"#);
+    };
+
+    // A format without any worthwhile variant gets no `<source>`
+    let source = |mime: &str, format: fn(&Variant) -> Option<&str>| {
+        let srcset = image
+            .variants
+            .iter()
+            .filter_map(|variant| Some(format!("{} {}w", format(variant)?, variant.width)))
+            .collect::<Vec<_>>();
+        match srcset.is_empty() {
+            true => String::new(),
+            false => format!(
+                r#"<source type="{mime}" srcset="{}" sizes="{SIZES}">"#,
+                srcset.join(", ")
+            ),
+        }
+    };
+
+    format!(
+        concat!(
+            "<picture>",
+            "{avif}{webp}",
+            r#"<img src="{src}" alt="{alt}"{title} width="{width}" height="{height}" loading="lazy" decoding="async">"#,
+            "</picture>",
+        ),
+        avif = source("image/avif", |variant| variant.avif),
+        webp = source("image/webp", |variant| variant.webp),
+        src = src,
+        alt = alt,
+        title = title,
+        width = image.width,
+        height = image.height,
+    )
+}
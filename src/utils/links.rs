@@ -0,0 +1,153 @@
+// External links in articles get the footer's treatment: a new tab and
+// `rel="noopener noreferrer"`, plus an icon so readers know they are leaving.
+// Markdown links are decorated in the tree, the anchors shortcodes write
+// into their raw HTML by `decorate`.
+
+use super::code::escape_html;
+
+pub struct ExternalLinks {
+    /// Hosts that are the site's own, their subdomains included.
+    pub internal_domains: &'static [&'static str],
+    /// Open in a new tab.
+    pub new_tab: bool,
+    /// Put an external-link icon after the text.
+    pub icon: bool,
+}
+
+pub const EXTERNAL_LINKS: ExternalLinks = ExternalLinks {
+    internal_domains: &["abhinandh-s.github.io"],
+    new_tab: true,
+    icon: true,
+};
+
+// Feather's `external-link`, like the footer icons
+const ICON: &str = concat!(
+    r#"<svg class="external-link-icon" aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="12" height="12" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#,
+    r#"<path d="M18 13v6a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V8a2 2 0 0 1 2-2h6"></path>"#,
+    r#"<polyline points="15 3 21 3 21 9"></polyline>"#,
+    r#"<line x1="10" y1="14" x2="21" y2="3"></line>"#,
+    "</svg>",
+);
+
+impl ExternalLinks {
+    /// Absolute `http(s)` or protocol-relative URLs to a host that isn't ours.
+    pub fn is_external(&self, url: &str) -> bool {
+        let Some(rest) = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .or_else(|| url.strip_prefix("//"))
+        else {
+            return false;
+        };
+
+        // `user@host:port`
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = host
+            .split(':')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        !self
+            .internal_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{domain}")))
+    }
+
+    /// The markup that goes before and after the text of a link to `url`.
+    pub fn tags(&self, url: &str, title: &str) -> (String, String) {
+        let title = match title {
+            "" => String::new(),
+            title => format!(r#" title="{}""#, escape_html(title)),
+        };
+        let target = match self.new_tab {
+            true => r#" target="_blank""#,
+            false => "",
+        };
+        let open = format!(
+            r#"<a href="{}"{title}{target} rel="noopener noreferrer" class="external-link">"#,
+            escape_html(url)
+        );
+
+        let mut close = String::new();
+        if self.icon {
+            close.push_str(ICON);
+        }
+        if self.new_tab {
+            close.push_str(r#"<span class="sr-only"> (opens in a new tab)</span>"#);
+        }
+        close.push_str("</a>");
+
+        (open, close)
+    }
+
+    /// `html` with every `<a href="...">` to an external URL given the same
+    /// markup as a markdown link. Only meant for shortcode output, whose
+    /// anchors are ours: a lone `href`, no nesting.
+    pub fn decorate(&self, html: &str) -> String {
+        let mut output = String::with_capacity(html.len());
+        let mut rest = html;
+        while let Some(start) = rest.find("<a ") {
+            output.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let Some((href, text, len)) = split_anchor(rest) else {
+                output.push_str("<a ");
+                rest = &rest["<a ".len()..];
+                continue;
+            };
+            let url = href.replace("&quot;", "\"").replace("&amp;", "&");
+            match self.is_external(&url) {
+                true => {
+                    let (open, close) = self.tags(&url, "");
+                    output.push_str(&format!("{open}{text}{close}"));
+                }
+                false => output.push_str(&rest[..len]),
+            }
+            rest = &rest[len..];
+        }
+        output.push_str(rest);
+        output
+    }
+}
+
+// `<a href="url">text</a>...` -> the url, the text and the anchor's length
+fn split_anchor(html: &str) -> Option<(&str, &str, usize)> {
+    let (href, after) = html.strip_prefix(r#"<a href=""#)?.split_once(r#"">"#)?;
+    if href.contains('"') {
+        return None;
+    }
+    let text = &after[..after.find("</a>")?];
+    let len = html.len() - after.len() + text.len() + "</a>".len();
+    Some((href, text, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_hosts() {
+        assert!(EXTERNAL_LINKS.is_external("https://example.com/a"));
+        assert!(EXTERNAL_LINKS.is_external("//user@example.com:8080"));
+        assert!(!EXTERNAL_LINKS.is_external("https://abhinandh-s.github.io/articles"));
+        assert!(!EXTERNAL_LINKS.is_external("https://blog.abhinandh-s.github.io"));
+        assert!(!EXTERNAL_LINKS.is_external("/articles"));
+        assert!(!EXTERNAL_LINKS.is_external("mailto:me@example.com"));
+    }
+
+    #[test]
+    fn decorates_shortcode_anchors() {
+        let (open, close) = EXTERNAL_LINKS.tags("https://gist.github.com/a/b?x=1&y=2", "");
+        assert_eq!(
+            EXTERNAL_LINKS.decorate(concat!(
+                r#"<div><a href="https://gist.github.com/a/b?x=1&amp;y=2">Gist</a>"#,
+                r#" <a href="/articles">Home</a> <a class="x">-</a></div>"#,
+            )),
+            format!(
+                r#"<div>{open}Gist{close} <a href="/articles">Home</a> <a class="x">-</a></div>"#
+            )
+        );
+    }
+}
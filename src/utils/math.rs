@@ -0,0 +1,27 @@
+// Server-side math: comrak's `$...$`, `$$...$$` and ```` ```math ```` nodes
+// become MathML, so nothing has to run in the browser.
+
+use latex2mathml::{DisplayStyle, latex_to_mathml};
+
+use super::code::escape_html;
+
+pub fn render_math(latex: &str, display: bool) -> String {
+    let style = if display {
+        DisplayStyle::Block
+    } else {
+        DisplayStyle::Inline
+    };
+
+    match latex_to_mathml(latex.trim(), style) {
+        // Keep the source around for screen readers and copy/paste
+        Ok(mathml) => mathml.replacen(
+            "<math",
+            &format!(r#"<math alttext="{}""#, escape_html(latex.trim())),
+            1,
+        ),
+        Err(_) => format!(
+            r#"<code class="math-error" title="Invalid math">{}</code>"#,
+            escape_html(latex)
+        ),
+    }
+}
@@ -1,10 +1,32 @@
 use std::collections::HashMap;
 
-use comrak::plugins::syntect::SyntectAdapterBuilder;
 use serde::{Deserialize, Serialize};
-use syntect::highlighting::ThemeSet;
 
+// The feed style is the feed generator's
+#[allow(dead_code)]
+mod alerts;
+// Discovery and asset copying run in build.rs, only link resolution here
+#[allow(dead_code)]
+mod bundles;
+mod code;
+mod diagrams;
+mod extensions;
 mod generated;
+mod headings;
+mod images;
+mod links;
+mod math;
+// Inline highlighter styles only come out of the feed generator
+#[allow(dead_code)]
+mod sanitize;
+mod shortcodes;
+mod stats;
+mod wikilinks;
+
+pub use code::escape_html;
+pub use extensions::{ExtensionOverrides, MarkdownExtensions, SITE_EXTENSIONS};
+pub use headings::HEADING_ID_PREFIX;
+pub use stats::ReadingStats;
 
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Article {
@@ -21,6 +43,98 @@ pub struct FrontMatter {
     pub published_at: String,
     pub snippet: String,
     pub tags: Option<Vec<String>>,
+    /// Name of the multi-part series this article belongs to.
+    pub series: Option<String>,
+    /// 1-based position inside `series`; falls back to publish date.
+    pub series_order: Option<u32>,
+    /// Set to `false` to hide the table of contents.
+    pub toc: Option<bool>,
+    /// Deepest heading level listed in the table of contents.
+    pub toc_depth: Option<u8>,
+    /// Turns optional markdown syntax on or off for this post only.
+    pub markdown: Option<ExtensionOverrides>,
+}
+
+impl Article {
+    pub fn stats(&self) -> ReadingStats {
+        stats::reading_stats(&self.content, &self.extensions())
+    }
+
+    pub fn extensions(&self) -> MarkdownExtensions {
+        SITE_EXTENSIONS.with_overrides(self.matter.markdown.as_ref())
+    }
+
+    /// Public URL of the article's assets, if it is a bundle directory.
+    pub fn asset_base(&self) -> Option<String> {
+        generated::BUNDLES
+            .contains(&self.id.as_str())
+            .then(|| format!("{}/{}", bundles::BUNDLES_URL, self.id))
+    }
+
+    pub fn route(&self) -> crate::Route {
+        let mut parts = self.matter.published_at.split('-');
+        crate::Route::Articles {
+            year: parts.next().unwrap_or("0000").to_string(),
+            month: parts.next().unwrap_or("00").to_string(),
+            id: self.id.clone(),
+        }
+    }
+}
+
+// `My Series: Part 1` -> `my-series-part-1`
+pub fn slugify(input: &str) -> String {
+    input
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Series {
+    pub slug: String,
+    pub name: String,
+    /// Ordered by `series_order`, then by publish date.
+    pub parts: Vec<Article>,
+}
+
+impl Series {
+    pub fn position(&self, id: &str) -> Option<usize> {
+        self.parts.iter().position(|a| a.id == id)
+    }
+}
+
+pub fn get_all_series() -> Vec<Series> {
+    let mut series: Vec<Series> = Vec::new();
+
+    for article in get_all_articles() {
+        let Some(name) = article.matter.series.clone() else {
+            continue;
+        };
+        let slug = slugify(&name);
+        match series.iter_mut().find(|s| s.slug == slug) {
+            Some(s) => s.parts.push(article),
+            None => series.push(Series {
+                slug,
+                name,
+                parts: vec![article],
+            }),
+        }
+    }
+
+    for s in &mut series {
+        s.parts.sort_by(|a, b| {
+            let key = |x: &Article| (x.matter.series_order.unwrap_or(u32::MAX), x.matter.published_at.clone());
+            key(a).cmp(&key(b))
+        });
+    }
+    series.sort_by(|a, b| a.name.cmp(&b.name));
+    series
+}
+
+pub fn get_series(slug: &str) -> Option<Series> {
+    get_all_series().into_iter().find(|s| s.slug == slug)
 }
 
 pub fn get_all_articles() -> Vec<Article> {
@@ -76,10 +190,170 @@ pub fn get_date(input: &str, long: bool) -> String {
     }
 }
 
+// input: `2024`, `06`
+// return it as [`June 2024`]
+pub fn get_month(year: &str, month: &str) -> String {
+    match chrono::NaiveDate::parse_from_str(&format!("{year}-{month}-01"), "%Y-%m-%d") {
+        Ok(date) => date.format("%B %Y").to_string(),
+        Err(err) => err.to_string(),
+    }
+}
+
+pub const ARTICLES_PER_PAGE: usize = 10;
+
+/// One page worth of articles, `number` is 1-based.
+#[derive(Clone, PartialEq)]
+pub struct Page {
+    pub number: usize,
+    pub total_pages: usize,
+    pub articles: Vec<Article>,
+}
+
+impl Page {
+    pub fn has_prev(&self) -> bool {
+        self.number > 1
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.number < self.total_pages
+    }
+}
+
+// Returns `None` for pages past the end, so the caller can 404.
+// An empty list still has a single (empty) first page.
+pub fn paginate(articles: Vec<Article>, number: usize, per_page: usize) -> Option<Page> {
+    let total_pages = articles.len().div_ceil(per_page).max(1);
+    if number == 0 || number > total_pages {
+        return None;
+    }
+
+    let articles = articles
+        .into_iter()
+        .skip((number - 1) * per_page)
+        .take(per_page)
+        .collect();
+
+    Some(Page {
+        number,
+        total_pages,
+        articles,
+    })
+}
+
+// `published_at` is `YYYY-MM-DD`, so the year and month are plain prefixes
+pub fn get_articles_by_date(year: &str, month: Option<&str>) -> Vec<Article> {
+    get_all_articles_sorted()
+        .into_iter()
+        .filter(|a| {
+            let mut parts = a.matter.published_at.split('-');
+            parts.next() == Some(year) && month.is_none_or(|m| parts.next() == Some(m))
+        })
+        .collect()
+}
+
+/// `(year, article count)`, latest year first.
+pub fn get_archive_years() -> Vec<(String, usize)> {
+    let mut years: Vec<(String, usize)> = Vec::new();
+
+    for article in get_all_articles_sorted() {
+        let year = article
+            .matter
+            .published_at
+            .split('-')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+
+        match years.last_mut() {
+            Some((last, count)) if *last == year => *count += 1,
+            _ => years.push((year, 1)),
+        }
+    }
+    years
+}
+
+/// `(year, month)` pairs that have at least one article, latest first.
+pub fn get_archive_months() -> Vec<(String, String)> {
+    let mut months: Vec<(String, String)> = Vec::new();
+
+    for article in get_all_articles_sorted() {
+        let mut parts = article.matter.published_at.split('-');
+        let year = parts.next().unwrap_or_default().to_string();
+        let month = parts.next().unwrap_or_default().to_string();
+
+        if months.last() != Some(&(year.clone(), month.clone())) {
+            months.push((year, month));
+        }
+    }
+    months
+}
+
 pub fn get_article_by_id(id: &str) -> Option<Article> {
     get_all_articles().into_iter().find(|f| f.id == id)
 }
 
+/// Chronological neighbours of `id`, as `(older, newer)`.
+pub fn get_adjacent_articles(id: &str) -> (Option<Article>, Option<Article>) {
+    let articles = get_all_articles_sorted();
+    let Some(idx) = articles.iter().position(|a| a.id == id) else {
+        return (None, None);
+    };
+
+    let older = articles.get(idx + 1).cloned();
+    let newer = idx.checked_sub(1).and_then(|i| articles.get(i)).cloned();
+    (older, newer)
+}
+
+// Lowercased words of title and snippet, used as a cheap similarity signal
+fn word_set(article: &Article) -> std::collections::HashSet<String> {
+    format!("{} {}", article.matter.title, article.matter.snippet)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Articles ranked by shared tags, ties broken by word overlap of title and
+/// snippet. Articles sharing nothing at all are left out.
+pub fn get_related_articles(id: &str, limit: usize) -> Vec<Article> {
+    let articles = get_all_articles_sorted();
+    let Some(current) = articles.iter().find(|a| a.id == id).cloned() else {
+        return Vec::new();
+    };
+
+    let tags = current.matter.tags.clone().unwrap_or_default();
+    let words = word_set(&current);
+
+    let mut scored: Vec<(usize, f32, Article)> = articles
+        .into_iter()
+        .filter(|a| a.id != current.id)
+        // Parts of the same series are already linked by the series navigator
+        .filter(|a| current.matter.series.is_none() || a.matter.series != current.matter.series)
+        .map(|a| {
+            let shared_tags = a
+                .matter
+                .tags
+                .iter()
+                .flatten()
+                .filter(|t| tags.iter().any(|c| c.eq_ignore_ascii_case(t)))
+                .count();
+            let other = word_set(&a);
+            let union = words.union(&other).count();
+            let similarity = match union {
+                0 => 0.0,
+                n => words.intersection(&other).count() as f32 / n as f32,
+            };
+            (shared_tags, similarity, a)
+        })
+        .filter(|(shared_tags, similarity, _)| *shared_tags > 0 || *similarity > 0.0)
+        .collect();
+
+    // Stable sort keeps latest-first order for equal scores
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.total_cmp(&a.1)));
+    scored.into_iter().take(limit).map(|(_, _, a)| a).collect()
+}
+
 pub fn get_articles_by_tag() -> HashMap<String, Vec<Article>> {
     let mut tag_map: HashMap<String, Vec<Article>> = HashMap::new();
     let articles = get_all_articles();
@@ -94,109 +368,401 @@ pub fn get_articles_by_tag() -> HashMap<String, Vec<Article>> {
     tag_map
 }
 
-/*
-base16-ocean.dark,base16-eighties.dark,base16-mocha.dark,base16-ocean.light
-InspiredGitHub from here
-Solarized (dark) and Solarized (light)
-*/
-pub fn markdown_to_html(source: &str) -> (Toc, String) {
+// Default syntaxes plus `static/syntaxes`, packed by build.rs. Decoding the
+// dump is not free, so it happens once.
+fn syntax_set() -> &'static syntect::parsing::SyntaxSet {
+    static SYNTAX_SET: std::sync::OnceLock<syntect::parsing::SyntaxSet> = std::sync::OnceLock::new();
+    SYNTAX_SET.get_or_init(|| {
+        syntect::dumps::from_binary(include_bytes!(concat!(
+            env!("OUT_DIR"),
+            "/syntaxes.packdump"
+        )))
+    })
+}
 
-    let latte = ("latte", include_str!("../../static/themes/Catppuccin Latte.tmTheme"));
-    let frappe = ("frappe", include_str!("../../static/themes/Catppuccin Frappe.tmTheme"));
-    let macchiato = ("macchiato", include_str!("../../static/themes/Catppuccin Macchiato.tmTheme"));
-    let mocha = ("mocha", include_str!("../../static/themes/Catppuccin Mocha.tmTheme"));
-  
-    // 2. Create a ThemeSet and add your theme to it
-    let mut themeset = ThemeSet::load_defaults();
-    
-    for (name, theme) in [latte, frappe, macchiato, mocha] {
-        let mut cursor = std::io::Cursor::new(theme);
-        let custom_theme = ThemeSet::load_from_reader(&mut cursor)
-            .expect("Failed to parse theme file");
+/// Articles with a wiki link to `id`, latest first.
+pub fn get_backlinks(id: &str) -> Vec<Article> {
+    get_all_articles_sorted()
+        .into_iter()
+        .filter(|a| {
+            a.id != id
+                && wikilinks::find(&a.content)
+                    .iter()
+                    .any(|(_, link)| link.id == id)
+        })
+        .collect()
+}
 
-        themeset.themes.insert(format!("catppuccin-{name}"), custom_theme);
+/// Where wiki links point: each article's path and title. build.rs writes
+/// the table once every link, and the heading it names, checks out.
+struct LinkTarget {
+    id: &'static str,
+    path: &'static str,
+    title: &'static str,
+}
 
+include!(concat!(env!("OUT_DIR"), "/wikilinks.rs"));
 
-    }
+/// `[[id#heading]]` -> (`/articles/2024/06/id#md-heading-heading`, title)
+pub fn resolve_wikilink(id: &str, heading: Option<&str>) -> Option<(String, String)> {
+    let target = LINK_TARGETS.iter().find(|target| target.id == id)?;
+    let fragment = heading
+        .map(|heading| format!("#{HEADING_ID_PREFIX}{heading}"))
+        .unwrap_or_default();
 
-    let adapter = SyntectAdapterBuilder::new()
-        .theme_set(themeset) // Use the set containing your theme
-        .theme("catppuccin-mocha") // Select it by the key used above
-                                                   // .theme_set(th_set)
-        .build();
+    Some((
+        format!("{}{fragment}", target.path),
+        target.title.to_string(),
+    ))
+}
 
+pub fn markdown_to_html(
+    source: &str,
+    extensions: &MarkdownExtensions,
+    asset_base: Option<&str>,
+) -> (Toc, String) {
+    // Code blocks are highlighted with CSS classes instead of inline colors.
+    // The colors for every Catppuccin flavor live in `static/syntax.css`,
+    // generated by build.rs from `static/themes`, so code follows the page theme.
+    let syntax_set = syntax_set();
+
+    let options = extensions.options();
+
+    // build.rs refuses to build with a link to an unknown article or heading,
+    // so an error here is content that bypassed it.
+    let source = match wikilinks::rewrite(source, |link| resolve_wikilink(link.id, link.heading)) {
+        Ok(source) => source,
+        Err(err) => {
+            return (
+                Toc::new(),
+                format!(
+                    r#"<p class="wikilink-error">Wiki link error, {}</p>"#,
+                    code::escape_html(&err.to_string())
+                ),
+            );
+        }
+    };
 
-    let mut options = comrak::Options::default();
-    options.extension.strikethrough = true;
-    options.extension.header_ids = Some("md-heading-".to_string());
-    options.extension.alerts = true;
-    options.extension.tasklist = true;
-    options.extension.spoiler = true;
+    // build.rs already refused to build with broken shortcodes, so this only
+    // trips on content that bypassed it; show the error instead of the post.
+    let expanded = match shortcodes::expand(&source) {
+        Ok(expanded) => expanded,
+        Err(err) => {
+            return (
+                Toc::new(),
+                format!(
+                    r#"<p class="shortcode-error">Shortcode error, {}</p>"#,
+                    code::escape_html(&err.to_string())
+                ),
+            );
+        }
+    };
 
-    let mut plugins = comrak::options::Plugins::default();
-    plugins.render.codefence_syntax_highlighter = Some(&adapter);
-    
     let arena = comrak::Arena::new();
-    let root = comrak::parse_document(&arena, source, &options);
-
-    // Helper to extract text from a node and its children
-    fn collect_text<'a>(node: &'a comrak::nodes::AstNode<'a>, output: &mut String) {
-        match node.data.borrow().value {
-            comrak::nodes::NodeValue::Text(ref t) => output.push_str(t),
-            comrak::nodes::NodeValue::Code(ref c) => output.push_str(&c.literal),
-            _ => {
-                for child in node.children() {
-                    collect_text(child, output);
+    let root = comrak::parse_document(&arena, &expanded.source, &options);
+
+    // Shortcode placeholders come out as paragraphs of a single text node
+    let placeholders = root
+        .descendants()
+        .filter_map(|node| {
+            let html = match (&node.data.borrow().value, node.first_child()) {
+                (comrak::nodes::NodeValue::Paragraph, Some(child))
+                    if child.next_sibling().is_none() =>
+                {
+                    match &child.data.borrow().value {
+                        // Their anchors skip the external link pass below
+                        comrak::nodes::NodeValue::Text(text) => expanded
+                            .lookup(text)
+                            .map(|html| links::EXTERNAL_LINKS.decorate(html)),
+                        _ => None,
+                    }
                 }
+                _ => None,
+            };
+            html.map(|html| (node, html))
+        })
+        .collect::<Vec<_>>();
+    for (node, html) in placeholders {
+        if let Some(child) = node.first_child() {
+            child.detach();
+        }
+        node.data.borrow_mut().value = comrak::nodes::NodeValue::Raw(html);
+    }
+
+    // Relative links and images of bundle articles point at their assets
+    if let Some(base) = asset_base {
+        for node in root.descendants() {
+            if let comrak::nodes::NodeValue::Link(link) | comrak::nodes::NodeValue::Image(link) =
+                &mut node.data.borrow_mut().value
+                && let Some(url) = bundles::resolve(&link.url, base)
+            {
+                link.url = url;
             }
         }
     }
 
-    let mut toc = Toc::new();
+    // The `<a>` of external links becomes raw markup around their own text
+    let external = root
+        .descendants()
+        .filter(|node| match &node.data.borrow().value {
+            comrak::nodes::NodeValue::Link(link) => links::EXTERNAL_LINKS.is_external(&link.url),
+            _ => false,
+        })
+        .collect::<Vec<_>>();
+    for node in external {
+        let (open, close) = match &node.data.borrow().value {
+            comrak::nodes::NodeValue::Link(link) => {
+                links::EXTERNAL_LINKS.tags(&link.url, &link.title)
+            }
+            _ => continue,
+        };
+        node.insert_before(arena.alloc(comrak::nodes::NodeValue::Raw(open).into()));
+        while let Some(child) = node.first_child() {
+            node.insert_before(child);
+        }
+        node.data.borrow_mut().value = comrak::nodes::NodeValue::Raw(close);
+    }
 
-    for node in root.children() {
-        if let comrak::nodes::NodeValue::Heading(heading) = &node.data.borrow().value {
-            let mut text = String::new();
-            collect_text(node, &mut text);
+    let mut toc = Toc::new();
+    let heading_ids = headings::heading_ids(root);
+
+    for (node, text, id) in &heading_ids {
+        let comrak::nodes::NodeValue::Heading(heading) = &node.data.borrow().value else {
+            continue;
+        };
+        // Only top-level section headings make it into the TOC. H1s are
+        // page-level titles and nested headings (blockquotes, list items)
+        // only get an id.
+        if heading.level > 1 && node.parent().is_some_and(|p| std::ptr::eq(p, root)) {
+            toc_insert(
+                &mut toc,
+                TocItem {
+                    level: heading.level,
+                    text: text.clone(),
+                    id: id.clone(),
+                    children: Vec::new(),
+                },
+            );
+        }
+    }
 
-            // Generate the ID. Comrak's default slugifier:
-            // 1. Lowercase
-            // 2. Remove non-alphanumeric (except hyphens/spaces)
-            // 3. Replace spaces with hyphens
-            let slug = text
-                .to_lowercase()
-                .replace(|c: char| !c.is_alphanumeric() && c != ' ', "")
-                .replace(' ', "-");
+    // Inside the heading, around the text comrak renders as usual
+    for (node, _, id) in heading_ids {
+        let anchor = comrak::nodes::NodeValue::Raw(headings::render_anchor(&id));
+        let copy = comrak::nodes::NodeValue::Raw(headings::render_copy_button(&id));
+        node.prepend(arena.alloc(anchor.into()));
+        node.append(arena.alloc(copy.into()));
+    }
 
-            let id = format!("{}{}", "md-heading-", slug);
+    // After the TOC, which only lists headings that are not inside an alert
+    alerts::render_alerts(root, alerts::Style::Site, |value| {
+        &*arena.alloc(value.into())
+    });
 
-            toc.push(TocItem {
-                level: heading.level,
-                text,
-                id,
-            });
+    // Swap every code block for our own markup (header bar, line spans),
+    // diagrams for the SVG build.rs rendered, math for MathML and images for
+    // `<picture>`.
+    // `Raw` nodes are written out verbatim, regardless of `render.r#unsafe`.
+    // Images lose their children below, so the nodes are collected up front.
+    let nodes = root.descendants().collect::<Vec<_>>();
+    for node in nodes {
+        let html = match &node.data.borrow().value {
+            comrak::nodes::NodeValue::Math(m) => math::render_math(&m.literal, m.display_math),
+            comrak::nodes::NodeValue::CodeBlock(block) if block.info.trim() == "math" => {
+                math::render_math(&block.literal, true)
+            }
+            comrak::nodes::NodeValue::CodeBlock(block) => {
+                match diagrams::lookup(&block.info, &block.literal) {
+                    Some(diagram) => diagram.to_string(),
+                    None => {
+                        let fence = code::FenceInfo::parse(&block.info);
+                        code::render_code_block(&fence, &block.literal, syntax_set)
+                    }
+                }
+            }
+            comrak::nodes::NodeValue::Image(link) => {
+                let mut alt = String::new();
+                headings::collect_text(node, &mut alt);
+                images::render_image(&link.url, &alt, &link.title)
+            }
+            _ => continue,
+        };
+        // The alt text of images is already rendered into the raw markup
+        while let Some(child) = node.first_child() {
+            child.detach();
         }
+        node.data.borrow_mut().value = comrak::nodes::NodeValue::Raw(html);
     }
 
     let mut html_output = String::new();
-    match comrak::format_html_with_plugins(root, &options, &mut html_output, &plugins) {
+    match comrak::format_html(root, &options, &mut html_output) {
         Ok(_) => (),
         Err(err) => {
             html_output.push_str(err.to_string().as_str());
         }
     }
 
+    // The page injects this unchecked, so only trusted posts skip the allowlist
+    if !extensions.raw_html {
+        html_output = sanitize::sanitize(&html_output);
+    }
+
     (
         toc,
         html_output,
     )
 }
 
-#[derive(PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct TocItem {
     pub level: u8,
     pub text: String,
     pub id: String,
+    /// Subsections, i.e. the following headings of a deeper level.
+    pub children: Vec<TocItem>,
+}
+
+impl TocItem {
+    pub fn href(&self) -> String {
+        format!("#{}", self.id)
+    }
 }
 
+/// Top-level entries of the table of contents, each one a tree.
 pub type Toc = Vec<TocItem>;
+
+pub const DEFAULT_TOC_DEPTH: u8 = 3;
+/// Posts with fewer headings than this get no table of contents.
+pub const MIN_TOC_HEADINGS: usize = 3;
+
+// Nest `item` under the last sibling of a shallower level. Skipped levels
+// (an H4 straight after an H2) simply nest one step down.
+fn toc_insert(siblings: &mut Toc, item: TocItem) {
+    match siblings.last_mut() {
+        Some(last) if last.level < item.level => toc_insert(&mut last.children, item),
+        _ => siblings.push(item),
+    }
+}
+
+fn toc_prune(toc: Toc, depth: u8) -> Toc {
+    toc.into_iter()
+        .filter(|item| item.level <= depth)
+        .map(|mut item| {
+            item.children = toc_prune(item.children, depth);
+            item
+        })
+        .collect()
+}
+
+fn toc_len(toc: &Toc) -> usize {
+    toc.iter().map(|item| 1 + toc_len(&item.children)).sum()
+}
+
+/// Applies the `toc` / `toc_depth` front matter to a table of contents.
+/// `None` when it is disabled or too short to be worth showing.
+pub fn select_toc(toc: Toc, matter: &FrontMatter) -> Option<Toc> {
+    if matter.toc == Some(false) {
+        return None;
+    }
+
+    let toc = toc_prune(toc, matter.toc_depth.unwrap_or(DEFAULT_TOC_DEPTH));
+    (toc_len(&toc) >= MIN_TOC_HEADINGS).then_some(toc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(source: &str) -> (Vec<String>, String) {
+        fn flatten(toc: &[TocItem], ids: &mut Vec<String>) {
+            for item in toc {
+                ids.push(item.id.clone());
+                flatten(&item.children, ids);
+            }
+        }
+
+        let (toc, html) = markdown_to_html(source, &SITE_EXTENSIONS, None);
+        let mut ids = Vec::new();
+        flatten(&toc, &mut ids);
+        (ids, html)
+    }
+
+    // Every TOC entry must point at an id that is actually in the page
+    fn assert_ids(source: &str, expected: &[&str]) {
+        let (ids, html) = render(source);
+        let expected = expected
+            .iter()
+            .map(|id| format!("{HEADING_ID_PREFIX}{id}"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, expected);
+        for id in &ids {
+            assert!(
+                html.contains(&format!(r#"id="{id}""#)),
+                "no `{id}` in {html}"
+            );
+        }
+    }
+
+    #[test]
+    fn duplicate_headings() {
+        assert_ids(
+            "## Intro\n\ntext\n\n## Intro\n\n## Intro\n",
+            &["intro", "intro-1", "intro-2"],
+        );
+    }
+
+    #[test]
+    fn punctuation_and_symbols() {
+        assert_ids("## C++ & Rust: what's new?\n", &["c--rust-whats-new"]);
+    }
+
+    #[test]
+    fn inline_markup() {
+        assert_ids(
+            "## The `Option` type\n\n## *Really* **fast**\n\n## See [the docs](https://docs.rs)\n",
+            &["the-option-type", "really-fast", "see-the-docs"],
+        );
+    }
+
+    #[test]
+    fn nested_levels() {
+        let source = "## One\n\n### One a\n\n#### Deep\n\n### One b\n\n## Two\n\n### One a\n";
+        assert_ids(source, &["one", "one-a", "deep", "one-b", "two", "one-a-1"]);
+
+        let (toc, _) = markdown_to_html(source, &SITE_EXTENSIONS, None);
+        assert_eq!(toc.len(), 2);
+        assert_eq!(toc[0].children.len(), 2);
+        assert_eq!(toc[0].children[0].children[0].id, "md-heading-deep");
+        assert_eq!(toc[1].children[0].id, "md-heading-one-a-1");
+    }
+
+    // Not in the TOC, but they still take their turn in the anchorizer
+    #[test]
+    fn headings_outside_the_toc() {
+        let (ids, html) = render("# Title\n\n> ## Intro\n\n## Intro\n");
+        assert_eq!(ids, ["md-heading-intro-1"]);
+        assert!(html.contains(r#"id="md-heading-title""#));
+        assert!(html.contains(r#"id="md-heading-intro""#));
+    }
+
+    // build.rs writes the paths without the router, they have to agree
+    #[test]
+    fn wikilinks_resolve_to_the_article_route() {
+        use yew_router::Routable;
+
+        for article in get_all_articles() {
+            let (path, title) = resolve_wikilink(&article.id, None).unwrap();
+            assert_eq!(path, article.route().to_path());
+            assert_eq!(title, article.matter.title);
+        }
+        assert_eq!(resolve_wikilink("no-such-article", None), None);
+    }
+
+    #[test]
+    fn external_links_in_shortcodes_and_alerts() {
+        let source = "{{< gist user/abc >}}\n\n> [!TIP]\n> See [x](https://example.com).\n";
+        let (_, html) = render(source);
+        let decorated = html.matches(r#"class="external-link""#).count();
+        assert_eq!(decorated, 2, "{html}");
+    }
+}
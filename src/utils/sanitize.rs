@@ -0,0 +1,267 @@
+// Allowlist sanitizer for rendered articles. Everything our own pipeline
+// emits (code blocks, MathML, diagram SVG, footnotes, shortcodes) is listed
+// below; anything else, raw HTML in a post included, is dropped. Posts with
+// `markdown: { raw_html: true }` are trusted and skip this stage.
+//
+// Shared with the feed generator, which includes this file by path, so feed
+// readers get the same markup as the site.
+
+use std::collections::{HashMap, HashSet};
+
+#[rustfmt::skip]
+pub const ALLOWED_TAGS: &[&str] = &[
+    // CommonMark and GFM
+    "a", "blockquote", "br", "code", "dd", "del", "div", "dl", "dt", "em", "h1", "h2", "h3", "h4",
+    "h5", "h6", "hr", "img", "input", "li", "ol", "p", "pre", "section", "span", "strong", "sub",
+    "sup", "table", "tbody", "td", "th", "thead", "tr", "ul",
+    // Responsive images, see `utils::images`
+    "picture", "source",
+    // Code block header, diagrams and shortcodes
+    "aside", "button", "figcaption", "figure", "iframe",
+    // MathML from latex2mathml
+    "math", "menclose", "mfrac", "mi", "mmultiscripts", "mn", "mo", "mover", "mpadded",
+    "mphantom", "mprescripts", "mroot", "mrow", "mspace", "msqrt", "mstyle", "msub", "msubsup",
+    "msup", "mtable", "mtd", "mtext", "mtr", "munder", "munderover", "none", "semantics",
+    "annotation",
+    // SVG from layout-rs
+    "circle", "clipPath", "defs", "ellipse", "g", "line", "marker", "path", "polygon", "polyline",
+    "rect", "svg", "text", "textPath", "tspan",
+];
+
+/// Attributes allowed on every tag. `data-*` is always allowed too.
+pub const GENERIC_ATTRIBUTES: &[&str] = &[
+    "class",
+    "id",
+    "title",
+    "role",
+    "aria-label",
+    "aria-hidden",
+    "lang",
+];
+
+#[rustfmt::skip]
+pub const TAG_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "target", "rel"]),
+    ("img", &["src", "alt", "width", "height", "loading", "decoding"]),
+    ("source", &["type", "srcset", "sizes"]),
+    ("input", &["type", "checked", "disabled"]),
+    ("ol", &["start"]),
+    ("th", &["align"]),
+    ("td", &["align"]),
+    ("button", &["type"]),
+    ("iframe", &["src", "loading", "allow", "allowfullscreen"]),
+    ("math", &["display", "alttext", "xmlns", "displaystyle", "scriptlevel", "mathvariant"]),
+    ("mi", &["mathvariant"]),
+    ("mo", &["stretchy", "fence", "separator", "lspace", "rspace", "form", "largeop",
+             "movablelimits", "accent"]),
+    ("mover", &["accent"]),
+    ("munder", &["accentunder"]),
+    ("munderover", &["accent", "accentunder"]),
+    ("mfrac", &["linethickness"]),
+    ("mspace", &["width"]),
+    ("mpadded", &["width", "height", "depth", "lspace", "voffset"]),
+    ("mstyle", &["displaystyle", "scriptlevel", "mathvariant"]),
+    ("menclose", &["notation"]),
+    ("mtable", &["columnalign", "rowalign", "columnspacing", "rowspacing"]),
+    ("mtd", &["columnalign", "columnspan", "rowspan"]),
+    ("annotation", &["encoding"]),
+    ("svg", &["width", "height", "viewBox", "xmlns", "fill", "stroke", "stroke-width",
+              "stroke-linecap", "stroke-linejoin"]),
+    ("marker", &["markerWidth", "markerHeight", "refX", "refY", "orient"]),
+    ("polygon", &["points", "fill", "stroke", "stroke-width"]),
+    ("polyline", &["points", "fill", "stroke", "stroke-width"]),
+    ("ellipse", &["cx", "cy", "rx", "ry", "fill", "stroke", "stroke-width"]),
+    ("circle", &["cx", "cy", "r", "fill", "stroke", "stroke-width"]),
+    ("rect", &["x", "y", "width", "height", "rx", "ry", "fill", "stroke", "stroke-width",
+               "clip-path"]),
+    ("line", &["x1", "y1", "x2", "y2", "stroke", "stroke-width", "stroke-dasharray"]),
+    ("path", &["d", "fill", "stroke", "stroke-width", "stroke-dasharray", "marker-start",
+               "marker-end", "clip-path"]),
+    ("text", &["x", "y", "dominant-baseline", "text-anchor", "fill", "clip-path"]),
+    ("tspan", &["x", "y", "dy"]),
+    ("textPath", &["href", "startOffset", "text-anchor"]),
+];
+
+/// Only these can be framed; the `youtube` shortcode produces the first.
+pub const IFRAME_ORIGINS: &[&str] = &["https://www.youtube-nocookie.com/embed/"];
+
+/// CSS properties the feed generator's highlighter sets in the `style` of
+/// `pre` and `span`, e.g. `color:#c0c5ce;`.
+pub const HIGHLIGHT_STYLES: &[&str] = &[
+    "color",
+    "background-color",
+    "font-weight",
+    "font-style",
+    "text-decoration",
+];
+
+pub fn sanitize(html: &str) -> String {
+    clean(html, false)
+}
+
+/// Like `sanitize`, but keeps the highlighter's inline colors. Any other
+/// `style`, from raw HTML in a post say, is still dropped.
+pub fn sanitize_feed(html: &str) -> String {
+    clean(html, true)
+}
+
+fn clean(html: &str, highlight_styles: bool) -> String {
+    let mut tag_attributes = TAG_ATTRIBUTES
+        .iter()
+        .map(|(tag, attributes)| (*tag, attributes.iter().copied().collect()))
+        .collect::<HashMap<_, HashSet<_>>>();
+    if highlight_styles {
+        for tag in ["pre", "span"] {
+            tag_attributes.entry(tag).or_default().insert("style");
+        }
+    }
+
+    let mut builder = ammonia::Builder::empty();
+    builder
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .generic_attributes(GENERIC_ATTRIBUTES.iter().copied().collect())
+        .generic_attribute_prefixes(HashSet::from(["data-"]))
+        .tag_attributes(tag_attributes)
+        .clean_content_tags(HashSet::from(["script", "style"]))
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        // Internal links stay as they are, `utils::links` sets `rel` on external ones
+        .link_rel(None)
+        .attribute_filter(|element, attribute, value| {
+            if element == "a" && attribute == "target" {
+                return (value == "_blank").then(|| value.into());
+            }
+            if element == "iframe" && attribute == "src" {
+                return IFRAME_ORIGINS
+                    .iter()
+                    .any(|origin| value.starts_with(origin))
+                    .then(|| value.into());
+            }
+            if attribute == "style" {
+                return is_highlight_style(value).then(|| value.into());
+            }
+            Some(value.into())
+        });
+
+    builder.clean(html).to_string()
+}
+
+// `color:#c0c5ce;font-weight:bold;`, plain keywords and colors only
+fn is_highlight_style(style: &str) -> bool {
+    style
+        .split(';')
+        .map(str::trim)
+        .filter(|declaration| !declaration.is_empty())
+        .all(|declaration| {
+            declaration
+                .split_once(':')
+                .is_some_and(|(property, value)| {
+                    let value = value.trim();
+                    HIGHLIGHT_STYLES.contains(&property.trim())
+                        && !value.is_empty()
+                        && value.chars().all(|c| c == '#' || c.is_ascii_alphanumeric())
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_scripts_and_handlers() {
+        let html = sanitize(
+            r#"<p onclick="alert(1)">hi<script>alert(2)</script></p><a href="javascript:alert(3)">x</a>"#,
+        );
+        assert_eq!(html, "<p>hi</p><a>x</a>");
+    }
+
+    #[test]
+    fn frames_only_allowed_origins() {
+        let html = sanitize(concat!(
+            r#"<iframe src="https://www.youtube-nocookie.com/embed/abc"></iframe>"#,
+            r#"<iframe src="https://evil.example/embed/abc"></iframe>"#,
+        ));
+        assert!(html.contains(r#"src="https://www.youtube-nocookie.com/embed/abc""#));
+        assert!(!html.contains("evil.example"));
+    }
+
+    #[test]
+    fn drops_styles() {
+        let html = r#"<span style="position:fixed;inset:0">x</span>"#;
+        assert_eq!(sanitize(html), "<span>x</span>");
+        assert_eq!(sanitize_feed(html), "<span>x</span>");
+    }
+
+    #[test]
+    fn feed_keeps_highlighter_colors() {
+        let html = concat!(
+            r#"<pre style="background-color:#2b303b;">"#,
+            r#"<span style="font-weight:bold;color:#b48ead;">fn</span>"#,
+            r#"<span style="color:url(https://evil.example/)">x</span></pre>"#,
+        );
+        assert_eq!(
+            sanitize_feed(html),
+            concat!(
+                r#"<pre style="background-color:#2b303b;">"#,
+                r#"<span style="font-weight:bold;color:#b48ead;">fn</span>"#,
+                "<span>x</span></pre>",
+            )
+        );
+        assert!(!sanitize(html).contains("style"));
+    }
+
+    #[test]
+    fn keeps_external_link_markup() {
+        let html = r#"<a href="https://example.com" target="_blank" rel="noopener noreferrer" class="external-link">x</a>"#;
+        assert_eq!(sanitize(html), html);
+        assert!(!sanitize(r#"<a href="/" target="_top">x</a>"#).contains("target"));
+    }
+
+    #[test]
+    fn keeps_diagram_svg() {
+        let html = concat!(
+            r#"<figure class="diagram" role="img" aria-label="Flow">"#,
+            r#"<svg width="300" height="80" viewBox="0 0 300 80" xmlns="http://www.w3.org/2000/svg">"#,
+            r#"<defs><marker id="diagram-1-endarrow" markerWidth="10" markerHeight="7" refX="0" refY="3.5" orient="auto">"#,
+            r#"<polygon points="0 0, 10 3.5, 0 7"></polygon></marker></defs>"#,
+            r##"<g><rect x="30" y="35" width="80" height="24" fill="#ffffffff" stroke-width="1" stroke="#000000ff" rx="0"></rect></g>"##,
+            r##"<text dominant-baseline="middle" text-anchor="middle" x="70" y="33" class="a14"><tspan x="70" dy="1.0em">Start</tspan></text>"##,
+            r##"<g><path id="diagram-1-arrow0" d="M 110 47 C 140 47, 134 47, 164 47" stroke="#000000ff" marker-end="url(#diagram-1-endarrow)" fill="transparent"></path></g>"##,
+            r##"<text><textPath href="#diagram-1-arrow0" startOffset="50%" text-anchor="middle" class="a14">go</textPath></text>"##,
+            "</svg><figcaption>Flow</figcaption></figure>",
+        );
+        assert_eq!(sanitize(html), html);
+    }
+
+    #[test]
+    fn keeps_mathml() {
+        let html = concat!(
+            r#"<math display="block"><mrow><msup><mi>x</mi><mn>2</mn></msup>"#,
+            r#"<mo stretchy="false">=</mo><mfrac><mi>a</mi><mi>b</mi></mfrac></mrow></math>"#,
+        );
+        assert_eq!(sanitize(html), html);
+    }
+
+    #[test]
+    fn keeps_pictures() {
+        let html = concat!(
+            "<picture>",
+            r#"<source type="image/avif" srcset="/static/variants/a-480.avif 480w" sizes="100vw">"#,
+            r#"<img src="/static/images/a.png" alt="A" width="480" height="320" loading="lazy" decoding="async">"#,
+            "</picture>",
+        );
+        assert_eq!(sanitize(html), html);
+    }
+
+    #[test]
+    fn keeps_alerts() {
+        let html = concat!(
+            r#"<div class="markdown-alert markdown-alert-tip" role="note">"#,
+            r#"<p class="markdown-alert-title">"#,
+            r#"<svg aria-hidden="true" xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">"#,
+            r#"<polygon points="13 2 3 14 12 14 11 22 21 10 12 10 13 2"></polygon></svg>Tip</p>"#,
+            "<p>Body</p></div>",
+        );
+        assert_eq!(sanitize(html), html);
+    }
+}
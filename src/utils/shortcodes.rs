@@ -0,0 +1,476 @@
+// Hugo-style shortcodes: `{{< youtube dQw4w9WgXcQ >}}` on a line of its own
+// expands to the HTML returned by the registered function. Block shortcodes
+// wrap markdown, `{{< callout warning >}} ... {{< /callout >}}`, and their
+// body is rendered like the rest of the post.
+//
+// Std only: build.rs includes this file by path to fail the build on broken
+// shortcodes, and so does the feed generator.
+
+use std::fmt;
+
+/// Arguments of a shortcode: bare or quoted words are positional,
+/// `key=value` / `key="some value"` are named.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    pub positional: Vec<String>,
+    pub named: Vec<(String, String)>,
+}
+
+impl Args {
+    /// Named argument `name`, or else the positional one at `index`.
+    pub fn get(&self, index: usize, name: &str) -> Option<&str> {
+        self.named
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .or_else(|| self.positional.get(index).map(String::as_str))
+    }
+
+    pub fn require(&self, index: usize, name: &str) -> Result<&str, String> {
+        self.get(index, name)
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| format!("missing `{}` argument", name))
+    }
+}
+
+pub enum Shortcode {
+    /// Replaced by the returned HTML.
+    Inline(fn(&Args) -> Result<String, String>),
+    /// Opening HTML from the function, the body as markdown, then `close`.
+    Block {
+        open: fn(&Args) -> Result<String, String>,
+        close: &'static str,
+    },
+}
+
+pub const SHORTCODES: &[(&str, Shortcode)] = &[
+    ("youtube", Shortcode::Inline(youtube)),
+    ("gist", Shortcode::Inline(gist)),
+    ("figure", Shortcode::Inline(figure)),
+    (
+        "callout",
+        Shortcode::Block {
+            open: callout,
+            close: "</aside>",
+        },
+    ),
+];
+
+#[derive(Debug, PartialEq)]
+pub struct ShortcodeError {
+    /// 1-based line of the offending shortcode.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ShortcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ShortcodeError {}
+
+/// Markdown with each shortcode swapped for a placeholder paragraph, and the
+/// HTML that belongs in place of each placeholder.
+#[derive(Debug, Default, PartialEq)]
+pub struct Expanded {
+    pub source: String,
+    pub html: Vec<String>,
+}
+
+impl Expanded {
+    /// The HTML for a paragraph whose whole text is `text`, if it is one of
+    /// our placeholders.
+    pub fn lookup(&self, text: &str) -> Option<&str> {
+        self.html.get(placeholder_index(text)?).map(String::as_str)
+    }
+}
+
+/// Whether `text` is a placeholder left by `expand`.
+pub fn is_placeholder(text: &str) -> bool {
+    placeholder_index(text).is_some()
+}
+
+fn placeholder_index(text: &str) -> Option<usize> {
+    text.strip_prefix(PLACEHOLDER_PREFIX)?
+        .strip_suffix(PLACEHOLDER_SUFFIX)?
+        .parse()
+        .ok()
+}
+
+// Plain letters and digits, so no markdown extension (emphasis, smart
+// punctuation, autolinks) rewrites it before we look it up.
+const PLACEHOLDER_PREFIX: &str = "SHORTCODEx";
+const PLACEHOLDER_SUFFIX: &str = "xEDOCTROHS";
+
+pub fn expand(source: &str) -> Result<Expanded, ShortcodeError> {
+    let mut expanded = Expanded::default();
+    let mut open_blocks: Vec<(&str, usize)> = Vec::new();
+    let mut fence: Option<String> = None;
+
+    for (idx, line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = line.trim();
+
+        // Shortcodes inside code fences are shown, not expanded
+        if let Some(marker) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                fence = None;
+            }
+            push_line(&mut expanded.source, line);
+            continue;
+        }
+        if let Some(marker) = fence_marker(trimmed) {
+            fence = Some(marker);
+            push_line(&mut expanded.source, line);
+            continue;
+        }
+
+        let Some(inner) = trimmed
+            .strip_prefix("{{<")
+            .and_then(|rest| rest.strip_suffix(">}}"))
+        else {
+            push_line(&mut expanded.source, line);
+            continue;
+        };
+
+        let error = |message: String| ShortcodeError {
+            line: line_no,
+            message,
+        };
+
+        let inner = inner.trim();
+        let html = if let Some(name) = inner.strip_prefix('/') {
+            let name = name.trim();
+            match open_blocks.pop() {
+                Some((open, _)) if open == name => block_close(name).map_err(error)?,
+                Some((open, opened_at)) => {
+                    return Err(error(format!(
+                        "`{{{{< /{} >}}}}` closes `{}` opened on line {}",
+                        name, open, opened_at
+                    )));
+                }
+                None => {
+                    return Err(error(format!(
+                        "`{{{{< /{} >}}}}` has no opening shortcode",
+                        name
+                    )));
+                }
+            }
+        } else {
+            let (name, args) = parse(inner).map_err(error)?;
+            let shortcode = lookup(name).ok_or_else(|| error(unknown(name)))?;
+            let html = match shortcode {
+                Shortcode::Inline(render) => render(&args),
+                Shortcode::Block { open, .. } => {
+                    open_blocks.push((name, line_no));
+                    open(&args)
+                }
+            };
+            html.map_err(|message| error(format!("{}: {}", name, message)))?
+        };
+
+        // Blank lines around the placeholder make it a paragraph of its own;
+        // keeping the indentation keeps it inside list items.
+        let indent = &line[..line.len() - line.trim_start().len()];
+        expanded.source.push('\n');
+        push_line(
+            &mut expanded.source,
+            &format!(
+                "{}{}{}{}",
+                indent,
+                PLACEHOLDER_PREFIX,
+                expanded.html.len(),
+                PLACEHOLDER_SUFFIX
+            ),
+        );
+        expanded.source.push('\n');
+        expanded.html.push(html);
+    }
+
+    if let Some((name, opened_at)) = open_blocks.pop() {
+        return Err(ShortcodeError {
+            line: opened_at,
+            message: format!("`{}` is never closed with `{{{{< /{} >}}}}`", name, name),
+        });
+    }
+
+    Ok(expanded)
+}
+
+fn push_line(output: &mut String, line: &str) {
+    output.push_str(line);
+    output.push('\n');
+}
+
+// ```` ``` ```` or `~~~`, three or more
+fn fence_marker(line: &str) -> Option<String> {
+    let ch = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then(|| ch.to_string().repeat(len))
+}
+
+fn lookup(name: &str) -> Option<&'static Shortcode> {
+    SHORTCODES
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .map(|(_, shortcode)| shortcode)
+}
+
+fn unknown(name: &str) -> String {
+    let known = SHORTCODES
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("unknown shortcode `{}` (known: {})", name, known)
+}
+
+fn block_close(name: &str) -> Result<String, String> {
+    match lookup(name) {
+        Some(Shortcode::Block { close, .. }) => Ok(close.to_string()),
+        Some(Shortcode::Inline(_)) => Err(format!("`{}` does not take a body", name)),
+        None => Err(unknown(name)),
+    }
+}
+
+// `name arg "quoted arg" key=value key="quoted value"`
+fn parse(inner: &str) -> Result<(&str, Args), String> {
+    let (name, rest) = inner.split_once(char::is_whitespace).unwrap_or((inner, ""));
+    if name.is_empty() {
+        return Err("empty shortcode".to_string());
+    }
+
+    let mut args = Args::default();
+    let mut chars = rest.trim().chars().peekable();
+
+    while chars.peek().is_some() {
+        let mut key = None;
+        let mut value = String::new();
+        let mut quoted = false;
+
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted => {
+                    quoted = false;
+                }
+                '"' if value.is_empty() => quoted = true,
+                '\\' if quoted => value.extend(chars.next()),
+                '=' if !quoted && key.is_none() => key = Some(std::mem::take(&mut value)),
+                c if c.is_whitespace() && !quoted => break,
+                c => value.push(c),
+            }
+        }
+        if quoted {
+            return Err(format!("unterminated quote in `{}`", inner));
+        }
+
+        match key {
+            Some(key) => args.named.push((key, value)),
+            None => args.positional.push(value),
+        }
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    Ok((name, args))
+}
+
+fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn is_slug(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+// {{< youtube dQw4w9WgXcQ [title="..."] >}}
+fn youtube(args: &Args) -> Result<String, String> {
+    let id = args.require(0, "id")?;
+    if !is_slug(id) {
+        return Err(format!("`{}` is not a video id", id));
+    }
+    let title = args.get(1, "title").unwrap_or("YouTube video");
+
+    Ok(format!(
+        r#"<div class="shortcode-youtube"><iframe src="https://www.youtube-nocookie.com/embed/{}" title="{}" loading="lazy" allow="accelerometer; clipboard-write; encrypted-media; gyroscope; picture-in-picture" allowfullscreen></iframe></div>"#,
+        id,
+        escape(title)
+    ))
+}
+
+// {{< gist user/0123abcd [file.rs] >}}
+fn gist(args: &Args) -> Result<String, String> {
+    let gist = args.require(0, "id")?;
+    let (user, id) = gist
+        .split_once('/')
+        .filter(|(user, id)| is_slug(user) && is_slug(id))
+        .ok_or_else(|| format!("expected `user/id`, got `{}`", gist))?;
+    // GitHub's anchor for `main.rs` is `#file-main-rs`
+    let file = args
+        .get(1, "file")
+        .map(|file| {
+            let anchor = file
+                .chars()
+                .map(|c| match c.is_ascii_alphanumeric() {
+                    true => c.to_ascii_lowercase(),
+                    false => '-',
+                })
+                .collect::<String>();
+            format!("#file-{anchor}")
+        })
+        .unwrap_or_default();
+
+    // GitHub only embeds gists with a script, which the sanitizer strips,
+    // so this is a link to the gist (or to the one file of it)
+    Ok(format!(
+        r#"<div class="shortcode-gist"><a href="https://gist.github.com/{user}/{id}{file}">View gist {user}/{id} on GitHub</a></div>"#,
+    ))
+}
+
+// {{< figure /static/img.png "Caption" [alt="..."] >}}
+fn figure(args: &Args) -> Result<String, String> {
+    let src = args.require(0, "src")?;
+    let caption = args.get(1, "caption");
+    let alt = args.get(2, "alt").or(caption).unwrap_or_default();
+
+    let caption = caption
+        .map(|caption| format!("<figcaption>{}</figcaption>", escape(caption)))
+        .unwrap_or_default();
+
+    Ok(format!(
+        r#"<figure class="shortcode-figure"><img src="{}" alt="{}" loading="lazy">{}</figure>"#,
+        escape(src),
+        escape(alt),
+        caption
+    ))
+}
+
+const CALLOUT_KINDS: &[&str] = &["note", "info", "tip", "warning", "danger"];
+
+// {{< callout warning ["Custom title"] >}} ... {{< /callout >}}
+fn callout(args: &Args) -> Result<String, String> {
+    let kind = args.get(0, "kind").unwrap_or("note");
+    if !CALLOUT_KINDS.contains(&kind) {
+        return Err(format!(
+            "unknown kind `{}` (expected one of: {})",
+            kind,
+            CALLOUT_KINDS.join(", ")
+        ));
+    }
+
+    let title = match args.get(1, "title") {
+        Some(title) => escape(title),
+        None => {
+            let mut title = kind.to_string();
+            title[..1].make_ascii_uppercase();
+            title
+        }
+    };
+
+    Ok(format!(
+        r#"<aside class="shortcode-callout callout-{}"><p class="callout-title">{}</p>"#,
+        kind, title
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(source: &str) -> ShortcodeError {
+        expand(source).unwrap_err()
+    }
+
+    #[test]
+    fn quoted_and_named_args() {
+        let (name, args) = parse(r#"figure /a.png "A \"big\" cat" alt="a cat" x="""#).unwrap();
+        assert_eq!(name, "figure");
+        assert_eq!(args.positional, ["/a.png", r#"A "big" cat"#]);
+        assert_eq!(args.get(2, "alt"), Some("a cat"));
+        assert_eq!(args.get(3, "x"), Some(""));
+        assert_eq!(
+            args.require(3, "x"),
+            Err("missing `x` argument".to_string())
+        );
+    }
+
+    #[test]
+    fn expands_to_placeholders() {
+        let expanded = expand("Intro\n  {{< youtube abc title=\"Demo\" >}}\n").unwrap();
+        assert_eq!(expanded.html.len(), 1);
+        assert!(expanded.html[0].contains("/embed/abc\" title=\"Demo\""));
+
+        let placeholder = expanded.source.lines().nth(2).unwrap();
+        assert_eq!(placeholder, "  SHORTCODEx0xEDOCTROHS");
+        assert_eq!(
+            expanded.lookup(placeholder.trim()),
+            Some(&*expanded.html[0])
+        );
+        assert!(is_placeholder(placeholder.trim()));
+    }
+
+    #[test]
+    fn block_shortcodes_wrap_markdown() {
+        let expanded =
+            expand("{{< callout warning \"Careful\" >}}\n*body*\n{{< /callout >}}\n").unwrap();
+        assert!(expanded.source.contains("\n*body*\n"));
+        assert_eq!(expanded.html[1], "</aside>");
+    }
+
+    #[test]
+    fn fences_are_left_alone() {
+        let source = "```\n{{< nope >}}\n```\n";
+        assert_eq!(expand(source).unwrap().source, source);
+    }
+
+    #[test]
+    fn unknown_shortcodes() {
+        let err = error("text\n\n{{< vimeo 123 >}}\n");
+        assert_eq!(err.line, 3);
+        assert!(
+            err.message.starts_with("unknown shortcode `vimeo`"),
+            "{err}"
+        );
+        assert_eq!(
+            error("{{< /vimeo >}}").message,
+            "`{{< /vimeo >}}` has no opening shortcode"
+        );
+    }
+
+    #[test]
+    fn unclosed_and_mismatched_blocks() {
+        let err = error("{{< callout >}}\nbody\n");
+        assert_eq!(err.line, 1);
+        assert_eq!(
+            err.message,
+            "`callout` is never closed with `{{< /callout >}}`"
+        );
+
+        let err = error("{{< callout >}}\n{{< /figure >}}\n");
+        assert_eq!(err.line, 2);
+        assert_eq!(
+            err.message,
+            "`{{< /figure >}}` closes `callout` opened on line 1"
+        );
+    }
+
+    #[test]
+    fn invalid_args() {
+        assert_eq!(
+            error(r#"{{< figure "/a.png >}}"#).message,
+            r#"unterminated quote in `figure "/a.png`"#
+        );
+        assert_eq!(
+            error("{{< youtube >}}").message,
+            "youtube: missing `id` argument"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+// Shared with `src/bin/generate_feeds.rs` through `#[path]`, so this module
+// must only depend on comrak, serde and the sibling modules the feed
+// generator includes as well.
+
+use comrak::nodes::{AstNode, NodeValue};
+use serde::{Deserialize, Serialize};
+
+use super::{extensions::MarkdownExtensions, shortcodes, wikilinks};
+
+pub const WORDS_PER_MINUTE: f32 = 230.0;
+// Code is read slower than prose, roughly three seconds a line
+pub const CODE_LINES_PER_MINUTE: f32 = 20.0;
+pub const SECONDS_PER_IMAGE: f32 = 12.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReadingStats {
+    /// Words of prose, inline code included, code blocks and image alt
+    /// text excluded.
+    pub words: usize,
+    /// Non-blank lines inside fenced or indented code blocks.
+    pub code_lines: usize,
+    pub images: usize,
+    /// Estimated reading time, never less than a minute.
+    pub minutes: usize,
+}
+
+/// Stats of a post's markdown, parsed with the post's own `extensions` so
+/// that table pipes and the like are not counted as words.
+pub fn reading_stats(source: &str, extensions: &MarkdownExtensions) -> ReadingStats {
+    let options = extensions.options();
+
+    // Wiki links read as their target's title, the id is close enough
+    let source = wikilinks::rewrite(source, |link| {
+        Some((String::new(), link.id.replace(['-', '_'], " ")))
+    })
+    .unwrap_or_else(|_| source.to_string());
+    // Embeds are looked at, not read, but the body of a block shortcode is
+    // prose like the rest of the post
+    let source = shortcodes::expand(&source)
+        .map(|expanded| expanded.source)
+        .unwrap_or(source);
+
+    let arena = comrak::Arena::new();
+    let root = comrak::parse_document(&arena, &source, &options);
+    reading_stats_of(root)
+}
+
+pub fn reading_stats_of<'a>(root: &'a AstNode<'a>) -> ReadingStats {
+    fn walk<'a>(node: &'a AstNode<'a>, stats: &mut ReadingStats) {
+        match node.data.borrow().value {
+            NodeValue::Text(ref t) if shortcodes::is_placeholder(t) => (),
+            NodeValue::Text(ref t) => stats.words += t.split_whitespace().count(),
+            NodeValue::Code(ref c) => stats.words += c.literal.split_whitespace().count(),
+            NodeValue::CodeBlock(ref c) => {
+                stats.code_lines += c.literal.lines().filter(|l| !l.trim().is_empty()).count()
+            }
+            // Alt text is not read, the image is looked at
+            NodeValue::Image(_) => stats.images += 1,
+            NodeValue::HtmlBlock(_) | NodeValue::HtmlInline(_) => (),
+            _ => {
+                for child in node.children() {
+                    walk(child, stats);
+                }
+            }
+        }
+    }
+
+    let mut stats = ReadingStats::default();
+    walk(root, &mut stats);
+
+    let minutes = stats.words as f32 / WORDS_PER_MINUTE
+        + stats.code_lines as f32 / CODE_LINES_PER_MINUTE
+        + stats.images as f32 * SECONDS_PER_IMAGE / 60.0;
+    stats.minutes = (minutes.ceil() as usize).max(1);
+    stats
+}
@@ -0,0 +1,232 @@
+// Wiki-style cross links: `[[article-id]]` and `[[article-id#heading]]`
+// become a regular markdown link to the article, with its title as the text,
+// or with a label of their own: `[[article-id|see here]]`. `heading` is the
+// heading's id without `HEADING_ID_PREFIX`, i.e. the slug comrak makes of the
+// heading text.
+//
+// Links resolve at build time: build.rs renders every article's headings,
+// fails on an unknown id or heading, and writes each article's URL and title
+// to the table `markdown_to_html` rewrites links from. The feed generator
+// resolves them against that same table.
+//
+// Std only: build.rs includes this file by path, and so does the feed
+// generator.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WikiLink<'a> {
+    pub id: &'a str,
+    pub heading: Option<&'a str>,
+    /// Markdown to use as the link text instead of the title.
+    pub label: Option<&'a str>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WikiLinkError {
+    /// 1-based line of the offending link.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for WikiLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for WikiLinkError {}
+
+/// Every wiki link in `source` with its 1-based line. Links inside code
+/// fences and code spans are text, not links.
+pub fn find(source: &str) -> Vec<(usize, WikiLink<'_>)> {
+    let mut links = Vec::new();
+    scan(source, |line, link| {
+        links.push((line, link));
+        None
+    });
+    links
+}
+
+/// `source` with every wiki link replaced by `[title](url)`. `resolve` gives
+/// the url and title of a link's target, `None` for an unknown article.
+pub fn rewrite(
+    source: &str,
+    resolve: impl Fn(&WikiLink) -> Option<(String, String)>,
+) -> Result<String, WikiLinkError> {
+    let mut error = None;
+    let output = scan(source, |line, link| match resolve(&link) {
+        Some((url, title)) => {
+            let text = link.label.map_or_else(|| escape(&title), str::to_string);
+            Some(format!("[{text}](<{url}>)"))
+        }
+        None => {
+            error.get_or_insert(WikiLinkError {
+                line,
+                message: format!("`[[{}]]` links to an unknown article", link.id),
+            });
+            None
+        }
+    });
+
+    match error {
+        Some(error) => Err(error),
+        None => Ok(output),
+    }
+}
+
+// Calls `on_link` for every link, and returns `source` with each link for
+// which it returned something replaced by that.
+fn scan<'a>(
+    source: &'a str,
+    mut on_link: impl FnMut(usize, WikiLink<'a>) -> Option<String>,
+) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut fence: Option<String> = None;
+
+    for (idx, line) in source.split_inclusive('\n').enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(marker) = &fence {
+            if trimmed.starts_with(marker.as_str()) {
+                fence = None;
+            }
+            output.push_str(line);
+            continue;
+        }
+        if let Some(marker) = fence_marker(trimmed) {
+            fence = Some(marker);
+            output.push_str(line);
+            continue;
+        }
+
+        let mut rest = line;
+        while !rest.is_empty() {
+            // Code spans are copied as is, backticks and all
+            if rest.starts_with('`') {
+                let ticks = rest.len() - rest.trim_start_matches('`').len();
+                let span = rest[ticks..]
+                    .find(&rest[..ticks])
+                    .map_or(ticks, |end| ticks + end + ticks);
+                output.push_str(&rest[..span]);
+                rest = &rest[span..];
+                continue;
+            }
+
+            if let Some((link, len)) = rest.strip_prefix("[[").and_then(parse) {
+                let raw = &rest[..len + 4];
+                match on_link(idx + 1, link) {
+                    Some(replacement) => output.push_str(&replacement),
+                    None => output.push_str(raw),
+                }
+                rest = &rest[raw.len()..];
+                continue;
+            }
+
+            // `\[[` is a literal bracket, skip past both characters
+            let skip = match rest.starts_with('\\') {
+                true => rest.chars().take(2).map(char::len_utf8).sum(),
+                false => rest.chars().next().map_or(1, char::len_utf8),
+            };
+            output.push_str(&rest[..skip]);
+            rest = &rest[skip..];
+        }
+    }
+
+    output
+}
+
+// `id]]...`, `id#heading]]...` or either with a `|label` -> the link and the
+// length of its inside. Anything else between double brackets, `[[1, 2], [3]]`
+// say, is left alone.
+fn parse(rest: &str) -> Option<(WikiLink<'_>, usize)> {
+    let inner = &rest[..rest.find("]]")?];
+    let (target, label) = match inner.split_once('|') {
+        Some((target, label)) => (target, Some(label.trim())),
+        None => (inner, None),
+    };
+    let (id, heading) = match target.split_once('#') {
+        Some((id, heading)) => (id, Some(heading)),
+        None => (target, None),
+    };
+
+    let is_slug = |value: &str| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    };
+    if !is_slug(id)
+        || heading.is_some_and(|heading| !is_slug(heading))
+        || label.is_some_and(str::is_empty)
+    {
+        return None;
+    }
+
+    Some((WikiLink { id, heading, label }, inner.len()))
+}
+
+// ```` ``` ```` or `~~~`, three or more
+fn fence_marker(line: &str) -> Option<String> {
+    let ch = line.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = line.chars().take_while(|c| *c == ch).count();
+    (len >= 3).then(|| ch.to_string().repeat(len))
+}
+
+// Titles are plain text, not markdown
+fn escape(input: &str) -> String {
+    input
+        .chars()
+        .flat_map(|c| match c.is_ascii_punctuation() {
+            true => vec!['\\', c],
+            false => vec![c],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(link: &WikiLink) -> Option<(String, String)> {
+        let fragment = link.heading.map(|h| format!("#{h}")).unwrap_or_default();
+        (link.id != "missing").then(|| (format!("/{}{fragment}", link.id), "A *title*".into()))
+    }
+
+    #[test]
+    fn finds_links_with_headings_and_labels() {
+        let links = find("[[a]] and [[b#intro]]\n[[c|see *this*]] [[d#setup|setup]]\n");
+        let link = |id, heading, label| WikiLink { id, heading, label };
+        assert_eq!(
+            links,
+            [
+                (1, link("a", None, None)),
+                (1, link("b", Some("intro"), None)),
+                (2, link("c", None, Some("see *this*"))),
+                (2, link("d", Some("setup"), Some("setup"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrites_to_markdown_links() {
+        assert_eq!(
+            rewrite("[[a]], [[a#intro]], [[a|label]]", resolve).unwrap(),
+            r"[A \*title\*](</a>), [A \*title\*](</a#intro>), [label](</a>)"
+        );
+    }
+
+    #[test]
+    fn leaves_code_and_other_brackets_alone() {
+        let source = "`[[a]]` [[1, 2], [3]] [[a|]] \\[[a]]\n```\n[[a]]\n```\n";
+        assert!(find(source).is_empty());
+        assert_eq!(rewrite(source, resolve).unwrap(), source);
+    }
+
+    #[test]
+    fn unknown_ids_are_errors() {
+        let err = rewrite("ok\n[[missing#intro]]\n", resolve).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.message, "`[[missing]]` links to an unknown article");
+    }
+}
@@ -0,0 +1,49 @@
+// Loaded blocking from <head>, so the stored theme is applied before first
+// paint and the page never flashes the wrong one. The Yew theme switcher
+// calls `window.__applyTheme` on change.
+(() => {
+  // Classes set on <html> per theme, see `@custom-variant dark` in
+  // input.css and the flavor selectors of the generated syntax.css
+  const CLASSES = {
+    latte: [],
+    frappe: ["dark", "frappe"],
+    macchiato: ["dark", "macchiato"],
+    mocha: ["dark"],
+  };
+  const media = matchMedia("(prefers-color-scheme: dark)");
+  const root = document.documentElement;
+
+  const apply = (theme) => {
+    if (theme !== "system" && !(theme in CLASSES)) theme = "system";
+    const flavor = theme === "system" ? (media.matches ? "mocha" : "latte") : theme;
+    root.classList.remove("dark", "frappe", "macchiato");
+    root.classList.add(...CLASSES[flavor]);
+    root.dataset.theme = theme;
+  };
+
+  window.__applyTheme = (theme) => {
+    try {
+      localStorage.setItem("theme", theme);
+    } catch (_) {
+      // Storage may be disabled, the cookie below still carries the choice
+    }
+    // Read by the server so SSR renders the switcher with the same value
+    document.cookie = `theme=${theme}; path=/; max-age=31536000; samesite=lax`;
+    apply(theme);
+  };
+
+  let stored = null;
+  try {
+    stored = localStorage.getItem("theme");
+  } catch (_) {
+    // Fall through to the cookie
+  }
+  // The server rendered the page for the cookie's theme, so it wins over
+  // the system theme when storage is empty or disabled
+  stored ??= document.cookie.match(/(?:^|;\s*)theme=([a-z]+)/)?.[1] ?? null;
+  apply(stored ?? "system");
+
+  media.addEventListener("change", () => {
+    if (root.dataset.theme === "system") apply("system");
+  });
+})();